@@ -0,0 +1,74 @@
+//! Loads every asset the tests use without panicking, so broken files can be reported
+//! instead of crashing [Example::new]
+
+use crate::scene;
+use pixels_graphics_lib::prelude::*;
+use std::path::Path;
+
+pub struct AssetResult {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+/// Check the embedded assets, then every scene file in [scene::SCENE_DIR]
+pub fn check_all() -> Vec<AssetResult> {
+    let mut results = vec![
+        check("test.ici_tileset", load_tileset().map(|_| ())),
+        check(
+            "test.tilemap",
+            load_tilemap(include_str!("../assets/test.tilemap")),
+        ),
+        check(
+            "test2.tilemap",
+            load_tilemap(include_str!("../assets/test2.tilemap")),
+        ),
+        check("test.ici", load_ici(include_bytes!("../assets/test.ici"))),
+        check("image.ici", load_ici(include_bytes!("../assets/image.ici"))),
+        check(
+            "static.ici",
+            load_ici(include_bytes!("../assets/static.ici")),
+        ),
+        check("slow.ica", load_ica(include_bytes!("../assets/slow.ica"))),
+        check("fast.ica", load_ica(include_bytes!("../assets/fast.ica"))),
+    ];
+    for path in scene::scene_files(Path::new(scene::SCENE_DIR)) {
+        let result = scene::Scene::load(&path)
+            .map(|_| ())
+            .map_err(|e| format!("{e:#}"));
+        results.push(check(&path.display().to_string(), result));
+    }
+    results
+}
+
+fn check(name: &str, result: Result<(), String>) -> AssetResult {
+    AssetResult {
+        name: name.to_string(),
+        error: result.err(),
+    }
+}
+
+fn load_tileset() -> Result<IciTileset, String> {
+    ron::from_str(include_str!("../assets/test.ici_tileset")).map_err(|e| format!("{e}"))
+}
+
+fn load_tilemap(text: &str) -> Result<(), String> {
+    let tileset = load_tileset()?
+        .into_tileset()
+        .map_err(|e| format!("tileset: {e:?}"))?;
+    let file: TilemapFile = ron::from_str(text).map_err(|e| format!("{e}"))?;
+    file.into_tilemap(&tileset, (200, 200))
+        .map(|_| ())
+        .map_err(|e| format!("{e:?}"))
+}
+
+fn load_ici(bytes: &[u8]) -> Result<(), String> {
+    IndexedImage::from_file_contents(bytes)
+        .map(|_| ())
+        .map_err(|e| format!("{e:?}"))
+}
+
+fn load_ica(bytes: &[u8]) -> Result<(), String> {
+    AnimatedIndexedImage::from_file_contents(bytes)
+        .map(|_| ())
+        .map_err(|e| format!("{e:?}"))
+}
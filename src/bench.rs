@@ -0,0 +1,160 @@
+//! Render benchmark
+//!
+//! Run with `graphics_tester bench [--iterations N] [--out FILE] [--compare FILE] [--threshold PERCENT]`,
+//! every test is rendered offscreen N times and the min, median and p99 frame times are written to
+//! `FILE`, along with any test that panicked. When a previous results file is given any test whose
+//! median got slower by more than the threshold, or that panics when it didn't before, is reported
+//! as a regression.
+
+use crate::checks::catch;
+use crate::Example;
+use anyhow::{bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: usize = 100;
+pub const DEFAULT_OUTPUT: &str = "bench.ron";
+/// Percentage slower a test's median can get before it's a regression
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResults {
+    pub iterations: usize,
+    pub tests: Vec<TestTiming>,
+    /// Tests that panicked and so have no timings, with the panic message
+    #[serde(default)]
+    pub panicked: Vec<(usize, String)>,
+}
+
+/// Frame times in microseconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestTiming {
+    pub test: usize,
+    pub min: f64,
+    pub median: f64,
+    pub p99: f64,
+}
+
+pub fn run(
+    iterations: usize,
+    output: &Path,
+    compare: Option<&Path>,
+    threshold: f64,
+    (width, height): (usize, usize),
+) -> Result<()> {
+    if iterations == 0 {
+        bail!("--iterations must be at least 1");
+    }
+    let previous = match compare {
+        Some(path) => Some(load(path)?),
+        None => None,
+    };
+
+    let mut example = Example::new();
+    let mut buffer = Graphics::create_buffer_u8(width, height);
+    let mut graphics = Graphics::new_u8_rgba(&mut buffer, width, height)?;
+
+    let mut results = BenchResults {
+        iterations,
+        tests: vec![],
+        panicked: vec![],
+    };
+    println!("test      min   median      p99  (us)");
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for test in 0..example.test_count() {
+        example.current_test = test;
+        let timed = catch(panic::AssertUnwindSafe(|| {
+            //first render fills caches and isn't counted
+            example.render(&mut graphics);
+            (0..iterations)
+                .map(|_| {
+                    let start = Instant::now();
+                    example.render(&mut graphics);
+                    start.elapsed()
+                })
+                .collect::<Vec<Duration>>()
+        }));
+        let mut times = match timed {
+            Ok(times) => times,
+            Err(message) => {
+                println!("{test:>4} {message}");
+                results.panicked.push((test, message));
+                continue;
+            }
+        };
+        times.sort();
+        let timing = TestTiming {
+            test,
+            min: micros(times[0]),
+            median: micros(times[times.len() / 2]),
+            p99: micros(times[(times.len() * 99).div_ceil(100) - 1]),
+        };
+        println!(
+            "{:>4} {:>8.1} {:>8.1} {:>8.1}",
+            timing.test, timing.min, timing.median, timing.p99
+        );
+        results.tests.push(timing);
+    }
+    panic::set_hook(default_hook);
+
+    let text = ron::ser::to_string_pretty(&results, ron::ser::PrettyConfig::default())?;
+    fs::write(output, text).with_context(|| format!("writing {}", output.display()))?;
+    println!("Results written to {}", output.display());
+
+    if let Some(previous) = previous {
+        let regressions = compare_results(&previous, &results, threshold);
+        if regressions > 0 {
+            bail!("{regressions} test(s) regressed by more than {threshold}%");
+        }
+        println!("No regressions above {threshold}%");
+    }
+    if !results.panicked.is_empty() {
+        bail!("{} test(s) panicked", results.panicked.len());
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<BenchResults> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    ron::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
+
+/// Print every test that got slower than `threshold` percent or now panics, returns how many did
+fn compare_results(previous: &BenchResults, current: &BenchResults, threshold: f64) -> usize {
+    let mut regressions = 0;
+    for (test, message) in &current.panicked {
+        if !previous.panicked.iter().any(|(before, _)| before == test) {
+            regressions += 1;
+            println!("[REGRESSION] {test}: {message}");
+        }
+    }
+    for timing in &current.tests {
+        let Some(before) = previous.tests.iter().find(|t| t.test == timing.test) else {
+            println!("[NEW] {}", timing.test);
+            continue;
+        };
+        if before.median <= 0.0 {
+            //too fast to have been timed, so there's no percentage to compare
+            println!("[SKIPPED] {}: previous median was 0us", timing.test);
+            continue;
+        }
+        let change = (timing.median - before.median) / before.median * 100.0;
+        if change > threshold {
+            regressions += 1;
+            println!(
+                "[REGRESSION] {}: median {:.1}us -> {:.1}us (+{change:.1}%)",
+                timing.test, before.median, timing.median
+            );
+        }
+    }
+    regressions
+}
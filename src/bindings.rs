@@ -0,0 +1,246 @@
+//! Key bindings for the window
+//!
+//! Every action has one or more keys, the defaults can be replaced per action by listing them in
+//! [BINDINGS_FILE], i.e.
+//!
+//! ```ron
+//! {
+//!     NextTest: ["ArrowRight", "KeyN"],
+//!     Screenshot: ["F12"],
+//! }
+//! ```
+//!
+//! Keys are named as [KeyCode]'s `Debug` prints them.
+
+use crate::input::{key_name, parse_key};
+use anyhow::{bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub const BINDINGS_FILE: &str = "bindings.ron";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    PreviousTest,
+    NextTest,
+    PreviousCategory,
+    NextCategory,
+    JumpToTilemap,
+    /// Stress page count or palette explorer threshold
+    Increase,
+    Decrease,
+    NextPaletteSource,
+    MapUp,
+    MapDown,
+    MapLeft,
+    MapRight,
+    SubtileUp,
+    SubtileDown,
+    SubtileLeft,
+    SubtileRight,
+    ToggleHud,
+    ToggleGrid,
+    ToggleLoupe,
+    ToggleHelp,
+    ToggleOverview,
+    ToggleCompare,
+    NextCompareMode,
+    /// Right hand test or time in the comparison
+    CompareOtherPrevious,
+    CompareOtherNext,
+    Screenshot,
+    ToggleRecording,
+    ResetSavedState,
+    Quit,
+}
+
+/// Default keys and help text for every action, in the order they're shown in the help
+const DEFAULTS: [(Action, &[KeyCode], &str); 29] = [
+    (Action::PreviousTest, &[KeyCode::ArrowLeft], "Previous test"),
+    (Action::NextTest, &[KeyCode::ArrowRight], "Next test"),
+    (
+        Action::PreviousCategory,
+        &[KeyCode::PageUp],
+        "Previous category",
+    ),
+    (Action::NextCategory, &[KeyCode::PageDown], "Next category"),
+    (Action::JumpToTilemap, &[KeyCode::Space], "Jump to test 61"),
+    (
+        Action::Increase,
+        &[KeyCode::ArrowUp],
+        "Stress x2 / threshold up",
+    ),
+    (
+        Action::Decrease,
+        &[KeyCode::ArrowDown],
+        "Stress /2 / threshold down",
+    ),
+    (
+        Action::NextPaletteSource,
+        &[KeyCode::Tab],
+        "Next palette image (64)",
+    ),
+    (Action::MapUp, &[KeyCode::KeyW], "Move map up (61)"),
+    (Action::MapDown, &[KeyCode::KeyS], "Move map down (61)"),
+    (Action::MapLeft, &[KeyCode::KeyA], "Move map left (61)"),
+    (Action::MapRight, &[KeyCode::KeyD], "Move map right (61)"),
+    (
+        Action::SubtileUp,
+        &[KeyCode::KeyI],
+        "Subtile offset up (61)",
+    ),
+    (
+        Action::SubtileDown,
+        &[KeyCode::KeyK],
+        "Subtile offset down (61)",
+    ),
+    (
+        Action::SubtileLeft,
+        &[KeyCode::KeyJ],
+        "Subtile offset left (61)",
+    ),
+    (
+        Action::SubtileRight,
+        &[KeyCode::KeyL],
+        "Subtile offset right (61)",
+    ),
+    (Action::ToggleHud, &[KeyCode::KeyH], "Timing HUD"),
+    (Action::ToggleGrid, &[KeyCode::KeyG], "Pixel grid"),
+    (Action::ToggleLoupe, &[KeyCode::KeyZ], "Loupe"),
+    (Action::ToggleHelp, &[KeyCode::F1], "This help"),
+    (
+        Action::ToggleOverview,
+        &[KeyCode::F2],
+        "Overview of all tests",
+    ),
+    (
+        Action::ToggleCompare,
+        &[KeyCode::F3],
+        "Side by side compare",
+    ),
+    (
+        Action::NextCompareMode,
+        &[KeyCode::F4],
+        "Tests / times / baseline",
+    ),
+    (
+        Action::CompareOtherPrevious,
+        &[KeyCode::BracketLeft],
+        "Compare previous (right)",
+    ),
+    (
+        Action::CompareOtherNext,
+        &[KeyCode::BracketRight],
+        "Compare next (right)",
+    ),
+    (Action::Screenshot, &[KeyCode::KeyP], "Screenshot"),
+    (
+        Action::ToggleRecording,
+        &[KeyCode::KeyR],
+        "Start/stop input recording",
+    ),
+    (
+        Action::ResetSavedState,
+        &[KeyCode::Backspace],
+        "Reset saved state",
+    ),
+    (Action::Quit, &[KeyCode::Escape], "Quit"),
+];
+
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    actions: Vec<(Action, Vec<KeyCode>)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            actions: DEFAULTS
+                .iter()
+                .map(|(action, keys, _)| (*action, keys.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+impl Bindings {
+    /// The defaults with any actions in `path` replaced, if it exists
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut bindings = Self::default();
+        if !path.exists() {
+            return Ok(bindings);
+        }
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let overrides: HashMap<Action, Vec<String>> =
+            ron::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        for (action, names) in overrides {
+            let mut keys = vec![];
+            for name in names {
+                match parse_key(&name) {
+                    Some(key) => keys.push(key),
+                    None => bail!("{}: unknown key {name} for {action:?}", path.display()),
+                }
+            }
+            if let Some((_, bound)) = bindings.actions.iter_mut().find(|(a, _)| *a == action) {
+                *bound = keys;
+            }
+        }
+        Ok(bindings)
+    }
+
+    pub fn is_bound(&self, action: Action, key: KeyCode) -> bool {
+        self.actions
+            .iter()
+            .any(|(a, keys)| *a == action && keys.contains(&key))
+    }
+
+    /// Every action bound to any of `keys`, in table order
+    pub fn actions_for(&self, keys: &[KeyCode]) -> Vec<Action> {
+        self.actions
+            .iter()
+            .filter(|(_, bound)| bound.iter().any(|key| keys.contains(key)))
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    pub fn render_help(&self, graphics: &mut Graphics) {
+        graphics.draw_rect(
+            Rect::new((4, 16), (245, 245)),
+            fill(Color::new(0, 0, 0, 220)),
+        );
+        let font = PixelFont::Standard4x5;
+        let line_height = font.line_height() as isize + 2;
+        let close = self
+            .actions
+            .iter()
+            .find(|(action, _)| *action == Action::ToggleHelp)
+            .and_then(|(_, keys)| keys.first())
+            .map(|key| key_name(*key))
+            .unwrap_or_default();
+        graphics.draw_text(
+            &format!("Keys ({close} to close)"),
+            TextPos::px(coord!(8, 20)),
+            (YELLOW, font),
+        );
+        for (i, (action, keys)) in self.actions.iter().enumerate() {
+            let y = 30 + i as isize * line_height;
+            let description = DEFAULTS
+                .iter()
+                .find(|(a, _, _)| a == action)
+                .map(|(_, _, description)| *description)
+                .unwrap_or_default();
+            let keys = keys.iter().map(|key| key_name(*key)).collect::<Vec<_>>();
+            let keys = if keys.is_empty() {
+                String::from("-")
+            } else {
+                keys.join(", ")
+            };
+            graphics.draw_text(description, TextPos::px(coord!(8, y)), (WHITE, font));
+            graphics.draw_text(&keys, TextPos::px(coord!(140, y)), (LIGHT_GRAY, font));
+        }
+    }
+}
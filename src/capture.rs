@@ -0,0 +1,160 @@
+use anyhow::{bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// File name (without extension) for captures of a test, i.e. `45_standard_4x4` for "45) Standard 4x4"
+pub fn file_stem(test: usize, title: &str) -> String {
+    let mut stem = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            stem.push(c.to_ascii_lowercase());
+        } else if !stem.is_empty() && !stem.ends_with('_') {
+            stem.push('_');
+        }
+    }
+    let stem = stem.trim_end_matches('_');
+    if stem.starts_with(&format!("{test}_")) {
+        stem.to_string()
+    } else if stem.is_empty() {
+        test.to_string()
+    } else {
+        format!("{test}_{stem}")
+    }
+}
+
+pub fn save_png(path: &Path, image: &Image) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        image.width() as u32,
+        image.height() as u32,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&rgba_bytes(image))?;
+    Ok(())
+}
+
+pub fn rgba_bytes(image: &Image) -> Vec<u8> {
+    image
+        .pixels()
+        .iter()
+        .flat_map(|color| [color.r, color.g, color.b, color.a])
+        .collect()
+}
+
+/// FNV-1a hash of the RGBA bytes of `image`
+pub fn frame_hash(image: &Image) -> u64 {
+    rgba_bytes(image)
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Write `graphics` to `dir/stem.png`, and `dir/stem.ici` if it has few enough colours
+///
+/// Returns the paths written and, if the `.ici` was skipped, why
+pub fn save_screenshot(
+    dir: &Path,
+    stem: &str,
+    graphics: &Graphics,
+) -> Result<(Vec<PathBuf>, Option<String>)> {
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let png_path = dir.join(format!("{stem}.png"));
+    save_png(&png_path, &graphics.copy_to_image())?;
+    let mut paths = vec![png_path];
+
+    let skipped = match graphics.copy_to_indexed_image(false) {
+        Ok(indexed) => {
+            let ici_path = dir.join(format!("{stem}.ici"));
+            let bytes = indexed
+                .to_file_contents(&FilePalette::Colors)
+                .map_err(|e| anyhow::anyhow!("encoding {}: {e:?}", ici_path.display()))?;
+            fs::write(&ici_path, bytes)
+                .with_context(|| format!("writing {}", ici_path.display()))?;
+            paths.push(ici_path);
+            None
+        }
+        Err(e) => Some(format!("no .ici written: {e}")),
+    };
+    Ok((paths, skipped))
+}
+
+pub fn save_gif(path: &Path, frames: &[Image], fps: usize) -> Result<()> {
+    let (width, height) = frame_size(frames)?;
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for image in frames {
+        let mut frame =
+            gif::Frame::from_rgba_speed(width as u16, height as u16, &mut rgba_bytes(image), 10);
+        frame.delay = (100 / fps.max(1)) as u16;
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+pub fn save_apng(path: &Path, frames: &[Image], fps: usize) -> Result<()> {
+    let (width, height) = frame_size(frames)?;
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len() as u32, 0)?;
+    encoder.set_frame_delay(1, fps as u16)?;
+    let mut writer = encoder.write_header()?;
+    for image in frames {
+        writer.write_image_data(&rgba_bytes(image))?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Fails if the frames use more than 255 colours between them, are over 255px wide or tall,
+/// or there are more than 255 frames
+pub fn save_ica(path: &Path, frames: &[Image], fps: usize) -> Result<()> {
+    let (width, height) = frame_size(frames)?;
+    let width = u8::try_from(width).context("frames are too wide for .ica")?;
+    let height = u8::try_from(height).context("frames are too tall for .ica")?;
+    let frame_count = u8::try_from(frames.len()).context("too many frames for .ica")?;
+    let mut palette: Vec<Color> = vec![];
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * frames.len());
+    for color in frames.iter().flat_map(|image| image.pixels()) {
+        let idx = match palette.iter().position(|c| c == color) {
+            Some(idx) => idx,
+            //the palette length is stored in a byte, so index 255 can't be used
+            None if palette.len() == u8::MAX as usize => bail!("frames use more than 255 colours"),
+            None => {
+                palette.push(*color);
+                palette.len() - 1
+            }
+        };
+        pixels.push(idx as u8);
+    }
+    let image = AnimatedIndexedImage::new(
+        width,
+        height,
+        1.0 / fps as f64,
+        frame_count,
+        palette,
+        pixels,
+        PlayType::Loops,
+    )
+    .map_err(|e| anyhow::anyhow!("creating animation: {e:?}"))?;
+    let bytes = image
+        .to_file_contents(&FilePalette::Colors)
+        .map_err(|e| anyhow::anyhow!("encoding {}: {e:?}", path.display()))?;
+    fs::write(path, bytes).with_context(|| format!("writing {}", path.display()))
+}
+
+fn frame_size(frames: &[Image]) -> Result<(usize, usize)> {
+    let first = frames.first().context("no frames recorded")?;
+    Ok((first.width(), first.height()))
+}
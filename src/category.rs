@@ -0,0 +1,152 @@
+//! Categories and tags of the tests
+//!
+//! Every test has one category and any number of tags, both can be used to pick tests with
+//! `check --include text,fonts --exclude animated`.
+
+use crate::TEST_COUNT;
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Text,
+    Shapes,
+    Rotation,
+    Collections,
+    Colour,
+    Images,
+    Fonts,
+    Tilemaps,
+    Stress,
+    /// Loaded from [crate::scene::SCENE_DIR]
+    Scenes,
+}
+
+impl Category {
+    /// In the order they're stepped through in the window
+    pub const ALL: [Category; 10] = [
+        Category::Text,
+        Category::Shapes,
+        Category::Rotation,
+        Category::Collections,
+        Category::Colour,
+        Category::Images,
+        Category::Fonts,
+        Category::Tilemaps,
+        Category::Stress,
+        Category::Scenes,
+    ];
+
+    pub fn of(test: usize) -> Category {
+        match test {
+            0..=2 | 25 | 36 => Category::Text,
+            14..=18 | 30..=32 => Category::Rotation,
+            23 | 24 => Category::Collections,
+            22 | 27 | 28 | 38 | 42 | 64 => Category::Colour,
+            26 | 29 | 35 | 39 | 43 | 44 | 57 | 63 => Category::Images,
+            37 | 45..=59 => Category::Fonts,
+            60..=62 => Category::Tilemaps,
+            65..=69 => Category::Stress,
+            n if n >= TEST_COUNT => Category::Scenes,
+            _ => Category::Shapes,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Text => "text",
+            Category::Shapes => "shapes",
+            Category::Rotation => "rotation",
+            Category::Collections => "collections",
+            Category::Colour => "colour",
+            Category::Images => "images",
+            Category::Fonts => "fonts",
+            Category::Tilemaps => "tilemaps",
+            Category::Stress => "stress",
+            Category::Scenes => "scenes",
+        }
+    }
+
+    /// The next (or previous) category that contains one of `0..test_count`, wrapping around
+    pub fn step(&self, forward: bool, test_count: usize) -> Category {
+        let index = Category::ALL.iter().position(|c| c == self).unwrap_or(0);
+        let len = Category::ALL.len();
+        (1..=len)
+            .map(|offset| {
+                let next = if forward {
+                    index + offset
+                } else {
+                    index + len - offset
+                };
+                Category::ALL[next % len]
+            })
+            .find(|category| category.first_test(test_count).is_some())
+            .unwrap_or(*self)
+    }
+
+    pub fn first_test(&self, test_count: usize) -> Option<usize> {
+        (0..test_count).find(|test| Category::of(*test) == *self)
+    }
+}
+
+pub const TAGS: [&str; 4] = ["animated", "interactive", "indexed", "offscreen"];
+
+pub fn tags(test: usize) -> Vec<&'static str> {
+    let mut tags = vec![];
+    if matches!(test, 14..=17 | 21 | 24 | 26 | 30..=32 | 66) {
+        tags.push("animated");
+    }
+    if matches!(test, 41 | 61 | 64..=69) {
+        tags.push("interactive");
+    }
+    if matches!(test, 26 | 29 | 39 | 42 | 43 | 44 | 57 | 60..=62 | 64 | 68) {
+        tags.push("indexed");
+    }
+    if matches!(test, 10..=12 | 33) {
+        tags.push("offscreen");
+    }
+    tags
+}
+
+/// True if `name` is `test`'s category or one of its tags
+pub fn has(test: usize, name: &str) -> bool {
+    Category::of(test).name() == name || tags(test).contains(&name)
+}
+
+/// Tests to include and exclude by category or tag
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// If empty every test is included
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Filter {
+    /// Split a comma separated list of category and tag names, failing on unknown names
+    pub fn parse_names(list: &str) -> Result<Vec<String>> {
+        let mut names = vec![];
+        for name in list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let name = name.to_lowercase();
+            let known = Category::ALL.iter().any(|category| category.name() == name)
+                || TAGS.contains(&name.as_str());
+            if !known {
+                let categories: Vec<&str> = Category::ALL.iter().map(Category::name).collect();
+                bail!(
+                    "unknown category or tag '{name}', expected one of {} or {}",
+                    categories.join(", "),
+                    TAGS.join(", ")
+                );
+            }
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    pub fn matches(&self, test: usize) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|name| has(test, name)))
+            && !self.exclude.iter().any(|name| has(test, name))
+    }
+}
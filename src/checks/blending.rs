@@ -0,0 +1,145 @@
+use crate::checks::SuiteResult;
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+
+const ALPHAS: [u8; 6] = [0, 1, 127, 128, 254, 255];
+/// Distance between cells, larger than a cell so a rect drawn one pixel too big doesn't touch its neighbour
+const CELL: usize = 5;
+/// Channel difference allowed for rounding when alpha isn't 0 or 255
+const TOLERANCE: u8 = 1;
+
+#[derive(Debug, Copy, Clone)]
+enum Method {
+    SetPixel,
+    FillRect,
+}
+
+/// Draw every source x destination x alpha combination offscreen and compare against the
+/// non-premultiplied "over" formula
+pub fn run(rng: &mut Rng) -> SuiteResult {
+    let mut result = SuiteResult::default();
+
+    let mut sources = vec![WHITE, BLACK, RED, GREEN, BLUE, MID_GRAY];
+    let mut destinations = vec![
+        WHITE,
+        BLACK,
+        RED,
+        CYAN,
+        Color::new(40, 90, 200, 128),
+        TRANSPARENT,
+    ];
+    for _ in 0..2 {
+        sources.push(Color::new(rng.u8(..), rng.u8(..), rng.u8(..), 255));
+        destinations.push(Color::new(rng.u8(..), rng.u8(..), rng.u8(..), rng.u8(..)));
+    }
+
+    let cols = sources.len() * ALPHAS.len();
+    let rows = destinations.len();
+    let (width, height) = (cols * CELL, rows * CELL);
+
+    for method in [Method::SetPixel, Method::FillRect] {
+        let mut buffer = Graphics::create_buffer_u8(width, height);
+        for (row, dst) in destinations.iter().enumerate() {
+            for col in 0..cols {
+                for y in 0..CELL {
+                    for x in 0..CELL {
+                        let idx = ((row * CELL + y) * width + col * CELL + x) * 4;
+                        buffer[idx..idx + 4].copy_from_slice(&[dst.r, dst.g, dst.b, dst.a]);
+                    }
+                }
+            }
+        }
+
+        {
+            let mut graphics = Graphics::new_u8_rgba(&mut buffer, width, height)
+                .expect("invalid blend buffer");
+            for row in 0..rows {
+                for (s, src) in sources.iter().enumerate() {
+                    for (a, alpha) in ALPHAS.iter().enumerate() {
+                        let src = Color { a: *alpha, ..*src };
+                        let pos = coord!(((s * ALPHAS.len() + a) * CELL), row * CELL);
+                        match method {
+                            Method::SetPixel => graphics.set_pixel(pos.x + 1, pos.y + 1, src),
+                            Method::FillRect => {
+                                graphics.draw_rect(Rect::new_with_size(pos, 3, 3), fill(src))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut max_error = 0;
+        for (row, dst) in destinations.iter().enumerate() {
+            for (s, src) in sources.iter().enumerate() {
+                for (a, alpha) in ALPHAS.iter().enumerate() {
+                    result.cases += 1;
+                    let src = Color { a: *alpha, ..*src };
+                    let x = (s * ALPHAS.len() + a) * CELL + 1;
+                    let y = row * CELL + 1;
+                    let idx = (y * width + x) * 4;
+                    let actual = Color::new(buffer[idx], buffer[idx + 1], buffer[idx + 2], buffer[idx + 3]);
+                    let expected = over(src, *dst);
+                    let error = channel_error(actual, expected);
+                    max_error = max_error.max(error);
+
+                    let input = format!("{method:?} {} over {}", src.to_hex(), dst.to_hex());
+                    match *alpha {
+                        0 if actual != *dst => result.fail(
+                            input,
+                            format!("alpha 0 wasn't a no-op, got {}", actual.to_hex()),
+                        ),
+                        255 if actual != src => result.fail(
+                            input,
+                            format!("alpha 255 didn't overwrite, got {}", actual.to_hex()),
+                        ),
+                        _ if error > TOLERANCE => result.fail(
+                            input,
+                            format!(
+                                "expected {} got {} (channel error {error})",
+                                expected.to_hex(),
+                                actual.to_hex()
+                            ),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        result.note(format!("{method:?}: max channel error {max_error}"));
+    }
+
+    result
+}
+
+/// Non-premultiplied source over destination
+fn over(src: Color, dst: Color) -> Color {
+    let sa = src.a as f64 / 255.0;
+    let da = dst.a as f64 / 255.0;
+    let out_a = sa + da * (1.0 - sa);
+    if out_a == 0.0 {
+        return TRANSPARENT;
+    }
+    let channel = |s: u8, d: u8| {
+        let value = (s as f64 * sa + d as f64 * da * (1.0 - sa)) / out_a;
+        value.round().clamp(0.0, 255.0) as u8
+    };
+    Color::new(
+        channel(src.r, dst.r),
+        channel(src.g, dst.g),
+        channel(src.b, dst.b),
+        (out_a * 255.0).round() as u8,
+    )
+}
+
+fn channel_error(lhs: Color, rhs: Color) -> u8 {
+    [
+        lhs.r.abs_diff(rhs.r),
+        lhs.g.abs_diff(rhs.g),
+        lhs.b.abs_diff(rhs.b),
+        lhs.a.abs_diff(rhs.a),
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or_default()
+}
@@ -0,0 +1,140 @@
+use crate::checks::gen::*;
+use crate::checks::{catch, SuiteResult};
+use fastrand::Rng;
+use pixels_graphics_lib::buffer_graphics_lib::GraphicsError as BufferGraphicsError;
+use pixels_graphics_lib::prelude::*;
+use std::collections::HashSet;
+
+/// Number of distinct colours scenes are built with, the last two can't fit in an [IndexedImage]
+const COLOR_COUNTS: [usize; 7] = [1, 2, 16, 128, 255, 256, 400];
+const SCENES_PER_COUNT: usize = 12;
+
+/// Convert random scenes Graphics -> IndexedImage -> Image -> Graphics and check nothing changed
+pub fn run(rng: &mut Rng) -> SuiteResult {
+    let mut result = SuiteResult::default();
+
+    for color_count in COLOR_COUNTS {
+        for _ in 0..SCENES_PER_COUNT {
+            let (seed, mut case_rng) = case_rng(rng);
+            let min_size = (color_count as f64).sqrt().ceil() as usize;
+            let width = case_rng.usize(min_size.max(1)..=64);
+            let height = case_rng.usize(min_size.max(1)..=64);
+            let mut buffer = scene(&mut case_rng, width, height, color_count);
+            let graphics = Graphics::new_u8_rgba(&mut buffer, width, height).expect("invalid scene buffer");
+            let original = graphics.copy_to_image();
+            let colors = original.pixels().iter().collect::<HashSet<_>>().len();
+
+            for simplify in [false, true] {
+                result.cases += 1;
+                let input = format!(
+                    "{width}x{height} scene with {colors} colors, copy_to_indexed_image({simplify}) (case seed {seed})"
+                );
+                if let Err(msg) = round_trip(&graphics, &original, colors, simplify) {
+                    result.fail(input, msg);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn round_trip(graphics: &Graphics, original: &Image, colors: usize, simplify: bool) -> Result<(), String> {
+    let indexed = match catch(|| graphics.copy_to_indexed_image(simplify))? {
+        Ok(indexed) if colors > 255 && !simplify => {
+            return Err(format!(
+                "expected TooManyColors for {colors} colors but got an image with {} palette entries",
+                indexed.get_palette().len()
+            ))
+        }
+        Ok(indexed) => indexed,
+        Err(BufferGraphicsError::TooManyColors) if colors > 255 && !simplify => return Ok(()),
+        Err(e) => return Err(format!("copy_to_indexed_image failed with {colors} colors: {e}")),
+    };
+
+    if indexed.size() != (original.width() as u8, original.height() as u8) {
+        return Err(format!("IndexedImage is {:?}", indexed.size()));
+    }
+    if indexed.get_palette().len() > 256 {
+        return Err(format!("palette has {} entries", indexed.get_palette().len()));
+    }
+    if colors > 255 {
+        // simplified so the pixels can't match
+        return Ok(());
+    }
+
+    let image = catch(|| Image::from_indexed(&indexed))?;
+    compare("Image::from_indexed", original, &image)?;
+
+    let mut buffer = Graphics::create_buffer_u8(image.width(), image.height());
+    let mut back = Graphics::new_u8_rgba(&mut buffer, image.width(), image.height())
+        .map_err(|e| e.to_string())?;
+    back.draw_image((0, 0), &image);
+    compare("Graphics::draw_image", original, &back.copy_to_image())
+}
+
+fn compare(stage: &str, expected: &Image, actual: &Image) -> Result<(), String> {
+    if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+        return Err(format!(
+            "{stage} changed size to {}x{}",
+            actual.width(),
+            actual.height()
+        ));
+    }
+    let diffs: Vec<usize> = expected
+        .pixels()
+        .iter()
+        .zip(actual.pixels())
+        .enumerate()
+        .filter(|(_, (e, a))| e != a)
+        .map(|(i, _)| i)
+        .collect();
+    match diffs.first() {
+        Some(i) => Err(format!(
+            "{stage} changed {} pixels, first at ({},{}) {} -> {}",
+            diffs.len(),
+            i % expected.width(),
+            i / expected.width(),
+            expected.pixels()[*i].to_hex(),
+            actual.pixels()[*i].to_hex()
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Draw a scene using exactly `color_count` distinct colours
+///
+/// Shapes are only drawn with the opaque colours so blending can't create extra colours,
+/// then every colour is written to at least one pixel
+fn scene(rng: &mut Rng, width: usize, height: usize, color_count: usize) -> Vec<u8> {
+    let mut colors: Vec<Color> = vec![];
+    while colors.len() < color_count {
+        let color = Color::new(rng.u8(..), rng.u8(..), rng.u8(..), if rng.bool() { 255 } else { rng.u8(1..255) });
+        if !colors.contains(&color) {
+            colors.push(color);
+        }
+    }
+    let mut buffer = Graphics::create_buffer_u8(width, height);
+    {
+        let mut graphics = Graphics::new_u8_rgba(&mut buffer, width, height).expect("invalid scene buffer");
+        graphics.clear(colors[0]);
+        let opaque: Vec<Color> = colors.iter().filter(|c| c.a == 255).copied().collect();
+        for _ in 0..rng.usize(0..8).min(opaque.len()) {
+            let color = opaque[rng.usize(..opaque.len())];
+            let start = coord!(rng.isize(-10..width as isize + 10), rng.isize(-10..height as isize + 10));
+            let end = coord!(rng.isize(-10..width as isize + 10), rng.isize(-10..height as isize + 10));
+            match rng.u8(..3) {
+                0 => graphics.draw_rect(Rect::new(start, end), fill(color)),
+                1 => graphics.draw_circle(Circle::new(start, rng.usize(1..20)), fill(color)),
+                _ => graphics.draw_line(start, end, color),
+            }
+        }
+    }
+    // drawing may have replaced some colours, put every one back on a distinct pixel
+    let mut pixels: Vec<usize> = (0..width * height).collect();
+    rng.shuffle(&mut pixels);
+    for (color, px) in colors.iter().zip(pixels) {
+        buffer[px * 4..px * 4 + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+    }
+    buffer
+}
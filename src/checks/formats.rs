@@ -0,0 +1,125 @@
+use crate::checks::{catch, SuiteResult};
+use crate::{current_title, Example, Stress, SCREEN_HEIGHT, SCREEN_WIDTH};
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+use std::panic;
+
+const WIDTH: usize = SCREEN_WIDTH as usize;
+const HEIGHT: usize = SCREEN_HEIGHT as usize;
+
+/// Render every test into each buffer type [Graphics] supports and compare them as RGBA
+///
+/// The buffers are decoded here rather than with [Graphics::copy_to_image] so a bug in the
+/// library's own conversion shows up too. The stress pages are skipped as they show timings.
+pub fn run(_: &mut Rng) -> SuiteResult {
+    let mut result = SuiteResult::default();
+    let mut example = Example::new();
+
+    for test in (0..example.test_count()).filter(|test| !Stress::is_stress(*test)) {
+        example.current_test = test;
+        result.cases += 1;
+
+        let reference = match render_u8_rgba(&mut example) {
+            Ok(pixels) => pixels,
+            Err(msg) => {
+                result.fail(title(test), format!("u8 RGBA {msg}"));
+                continue;
+            }
+        };
+        let others = [
+            ("u32 RGBA", render_u32(&mut example, false)),
+            ("u32 ARGB", render_u32(&mut example, true)),
+        ];
+        for (name, pixels) in others {
+            let msg = match pixels {
+                Ok(pixels) => match compare(&reference, &pixels) {
+                    Some(msg) => msg,
+                    None => continue,
+                },
+                Err(msg) => msg,
+            };
+            result.fail(title(test), format!("{name} vs u8 RGBA: {msg}"));
+        }
+    }
+
+    result
+}
+
+fn title(test: usize) -> String {
+    match current_title() {
+        title if title.is_empty() => format!("test {test}"),
+        title => title,
+    }
+}
+
+fn render_u8_rgba(example: &mut Example) -> Result<Vec<Color>, String> {
+    let mut buffer = Graphics::create_buffer_u8(WIDTH, HEIGHT);
+    {
+        let mut graphics =
+            Graphics::new_u8_rgba(&mut buffer, WIDTH, HEIGHT).map_err(|e| e.to_string())?;
+        catch(panic::AssertUnwindSafe(|| example.render(&mut graphics)))?;
+    }
+    Ok(buffer
+        .chunks_exact(4)
+        .map(|px| Color::new(px[0], px[1], px[2], px[3]))
+        .collect())
+}
+
+fn render_u32(example: &mut Example, argb: bool) -> Result<Vec<Color>, String> {
+    let mut buffer = Graphics::create_buffer_u32(WIDTH, HEIGHT);
+    {
+        let mut graphics = if argb {
+            Graphics::new_u32_argb(&mut buffer, WIDTH, HEIGHT)
+        } else {
+            Graphics::new_u32_rgba(&mut buffer, WIDTH, HEIGHT)
+        }
+        .map_err(|e| e.to_string())?;
+        catch(panic::AssertUnwindSafe(|| example.render(&mut graphics)))?;
+    }
+    let decode = if argb { from_argb } else { from_rgba };
+    Ok(buffer.into_iter().map(decode).collect())
+}
+
+fn from_rgba(value: u32) -> Color {
+    let [r, g, b, a] = value.to_be_bytes();
+    Color::new(r, g, b, a)
+}
+
+fn from_argb(value: u32) -> Color {
+    let [a, r, g, b] = value.to_be_bytes();
+    Color::new(r, g, b, a)
+}
+
+/// Describe how `actual` differs from `expected`, grouping differences by likely cause
+fn compare(expected: &[Color], actual: &[Color]) -> Option<String> {
+    let mut first = None;
+    let mut alpha_only = 0;
+    let mut reordered = 0;
+    let mut other = 0;
+    for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+        if e == a {
+            continue;
+        }
+        first.get_or_insert((i % WIDTH, i / WIDTH, *e, *a));
+        if (e.r, e.g, e.b) == (a.r, a.g, a.b) {
+            alpha_only += 1;
+        } else if sorted_channels(e) == sorted_channels(a) {
+            reordered += 1;
+        } else {
+            other += 1;
+        }
+    }
+    let (x, y, e, a) = first?;
+    Some(format!(
+        "{} pixels differ ({alpha_only} alpha only, {reordered} channels reordered, {other} other), first at ({x}, {y}): expected {:?}, got {:?}",
+        alpha_only + reordered + other,
+        (e.r, e.g, e.b, e.a),
+        (a.r, a.g, a.b, a.a)
+    ))
+}
+
+fn sorted_channels(color: &Color) -> [u8; 4] {
+    let mut channels = [color.r, color.g, color.b, color.a];
+    channels.sort();
+    channels
+}
@@ -0,0 +1,63 @@
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+
+/// Image sizes that historically cause problems: 1 pixel, odd, even, non-square and the max size
+pub const EDGE_SIZES: [(u8, u8); 10] = [
+    (1, 1),
+    (1, 2),
+    (2, 1),
+    (2, 2),
+    (3, 3),
+    (3, 5),
+    (7, 4),
+    (16, 16),
+    (127, 63),
+    (255, 255),
+];
+
+pub fn color(rng: &mut Rng) -> Color {
+    Color::new(rng.u8(..), rng.u8(..), rng.u8(..), rng.u8(..))
+}
+
+pub fn palette(rng: &mut Rng, len: usize) -> Vec<Color> {
+    (0..len).map(|_| color(rng)).collect()
+}
+
+/// Random image using every palette index at least once where the image is big enough
+pub fn indexed_image(rng: &mut Rng, width: u8, height: u8, palette_len: usize) -> IndexedImage {
+    let count = width as usize * height as usize;
+    let mut pixels: Vec<u8> = (0..count)
+        .map(|_| rng.usize(..palette_len) as u8)
+        .collect();
+    for (i, px) in pixels.iter_mut().take(palette_len).enumerate() {
+        *px = i as u8;
+    }
+    rng.shuffle(&mut pixels);
+    IndexedImage::new(width, height, palette(rng, palette_len), pixels)
+        .expect("generated invalid image")
+}
+
+/// Describe an image well enough to recreate it
+pub fn describe_indexed(image: &IndexedImage) -> String {
+    let (w, h) = image.size();
+    let palette_len = image.get_palette().len();
+    if image.get_pixels().len() <= 64 {
+        format!("{w}x{h} palette {palette_len} pixels {:?}", image.get_pixels())
+    } else {
+        format!("{w}x{h} palette {palette_len}")
+    }
+}
+
+/// Split off a generator for a single case so it can be recreated from its seed
+pub fn case_rng(rng: &mut Rng) -> (u64, Rng) {
+    let seed = rng.u64(..);
+    (seed, Rng::with_seed(seed))
+}
+
+/// Random image with roughly a quarter of the pixels transparent
+pub fn image(rng: &mut Rng, width: usize, height: usize) -> Image {
+    let pixels = (0..width * height)
+        .map(|_| if rng.u8(..4) == 0 { TRANSPARENT } else { color(rng) })
+        .collect();
+    Image::new(pixels, width, height).expect("generated invalid image")
+}
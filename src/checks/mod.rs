@@ -0,0 +1,148 @@
+//! Headless checks that assert library invariants instead of relying on someone
+//! comparing test pages by eye.
+//!
+//! Run with `graphics_tester verify [suite] [--seed N] [--all]`.
+
+mod blending;
+mod conversion;
+mod formats;
+mod gen;
+pub mod scaling;
+mod transforms;
+mod unchecked;
+
+use anyhow::{bail, Result};
+use fastrand::Rng;
+use std::any::Any;
+use std::panic;
+use std::panic::UnwindSafe;
+
+pub const DEFAULT_SEED: u64 = 0x6772_6170_6869_6373;
+/// Number of failures printed per suite unless `--all` is passed
+const MAX_PRINTED: usize = 25;
+
+pub struct Suite {
+    pub name: &'static str,
+    pub run: fn(&mut Rng) -> SuiteResult,
+}
+
+pub const SUITES: &[Suite] = &[
+    Suite {
+        name: "indexed_unchecked",
+        run: unchecked::run,
+    },
+    Suite {
+        name: "scaling",
+        run: scaling::run,
+    },
+    Suite {
+        name: "alpha_blending",
+        run: blending::run,
+    },
+    Suite {
+        name: "image_transforms",
+        run: transforms::run,
+    },
+    Suite {
+        name: "indexed_conversion",
+        run: conversion::run,
+    },
+    Suite {
+        name: "buffer_formats",
+        run: formats::run,
+    },
+];
+
+#[derive(Debug, Default)]
+pub struct SuiteResult {
+    pub cases: usize,
+    pub failures: Vec<Failure>,
+    /// Measurements printed whether or not the suite passed
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    /// Description of the input that caused the failure
+    pub input: String,
+    pub message: String,
+}
+
+impl SuiteResult {
+    pub fn fail<S: Into<String>, M: Into<String>>(&mut self, input: S, message: M) {
+        self.failures.push(Failure {
+            input: input.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn note<S: Into<String>>(&mut self, note: S) {
+        self.notes.push(note.into());
+    }
+}
+
+/// Run `method`, converting a panic into an error containing the panic message
+pub fn catch<T, F: FnOnce() -> T + UnwindSafe>(method: F) -> Result<T, String> {
+    panic::catch_unwind(method).map_err(panic_message)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        format!("panicked: {msg}")
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        format!("panicked: {msg}")
+    } else {
+        String::from("panicked")
+    }
+}
+
+/// Run every suite (or only those whose name contains `filter`) and fail if any invariant was broken
+pub fn run(filter: Option<&str>, seed: u64, print_all: bool) -> Result<()> {
+    let suites: Vec<&Suite> = SUITES
+        .iter()
+        .filter(|suite| filter.map(|f| suite.name.contains(f)).unwrap_or(true))
+        .collect();
+    if suites.is_empty() {
+        bail!("No suites match {filter:?}");
+    }
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut failed = 0;
+    for suite in suites {
+        let mut rng = Rng::with_seed(seed);
+        let result = (suite.run)(&mut rng);
+        if result.failures.is_empty() {
+            println!("[PASS] {} ({} cases)", suite.name, result.cases);
+            for note in &result.notes {
+                println!("  {note}");
+            }
+        } else {
+            println!(
+                "[FAIL] {} ({} of {} cases failed)",
+                suite.name,
+                result.failures.len(),
+                result.cases
+            );
+            for note in &result.notes {
+                println!("  {note}");
+            }
+            let limit = if print_all { usize::MAX } else { MAX_PRINTED };
+            for failure in result.failures.iter().take(limit) {
+                println!("  {}\n    {}", failure.input, failure.message);
+            }
+            if result.failures.len() > limit {
+                println!("  ...and {} more (use --all)", result.failures.len() - limit);
+            }
+            failed += 1;
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    if failed > 0 {
+        bail!("{failed} suite(s) failed (seed {seed})");
+    }
+    Ok(())
+}
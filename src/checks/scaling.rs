@@ -0,0 +1,159 @@
+use crate::checks::gen::*;
+use crate::checks::{catch, SuiteResult};
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+
+/// Nearest neighbour factors checked for every input, including asymmetric and prime factors
+const NN_FACTORS: [(usize, usize); 9] = [
+    (1, 1),
+    (2, 2),
+    (1, 2),
+    (2, 1),
+    (3, 3),
+    (3, 2),
+    (5, 7),
+    (7, 7),
+    (11, 3),
+];
+
+/// Random sizes checked on top of [EDGE_SIZES]
+const RANDOM_SIZES: usize = 40;
+
+/// Check output sizes of every [Scaling] and that nearest neighbour scaling can be undone
+pub fn run(rng: &mut Rng) -> SuiteResult {
+    let mut result = SuiteResult::default();
+
+    for (x, y) in [(0, 1), (1, 0), (0, 0)] {
+        result.cases += 1;
+        if Scaling::nearest_neighbour(x, y).is_ok() {
+            result.fail(
+                format!("Scaling::nearest_neighbour({x}, {y})"),
+                "accepted a zero factor",
+            );
+        }
+    }
+
+    let mut sizes = EDGE_SIZES.to_vec();
+    for _ in 0..RANDOM_SIZES {
+        sizes.push((rng.u8(1..=64), rng.u8(1..=64)));
+    }
+
+    for (w, h) in sizes {
+        let (seed, mut case_rng) = case_rng(rng);
+        let palette_len = case_rng.usize(1..=16);
+        let indexed = indexed_image(&mut case_rng, w, h, palette_len);
+        let image = self::image(&mut case_rng, w as usize, h as usize);
+
+        let mut algos = vec![Scaling::Epx2x, Scaling::Epx4x];
+        for (x, y) in NN_FACTORS {
+            algos.push(Scaling::nearest_neighbour(x, y).unwrap());
+        }
+
+        for algo in algos {
+            let (exp_w, exp_h) = expected_size(w as usize, h as usize, algo);
+
+            result.cases += 1;
+            let input = format!("{} on IndexedImage {w}x{h} (case seed {seed})", name(algo));
+            match catch(|| indexed.scale(algo)) {
+                Ok(Ok(scaled)) => {
+                    let actual = (scaled.width() as usize, scaled.height() as usize);
+                    if actual != (exp_w, exp_h) {
+                        result.fail(&input, format!("expected {exp_w}x{exp_h}, got {}x{}", actual.0, actual.1));
+                    } else if let Scaling::NearestNeighbour { x_scale, y_scale } = algo {
+                        let (x_scale, y_scale) = (x_scale.get(), y_scale.get());
+                        let restored = downscale_indexed(&scaled, x_scale, y_scale);
+                        if let Some((x, y)) = first_block_diff(indexed.get_pixels(), w as usize, scaled.get_pixels(), x_scale, y_scale) {
+                            result.fail(&input, format!("pixel {x},{y} differs from the source pixel it was scaled from"));
+                        } else if let Some(i) = first_diff(indexed.get_pixels(), restored.get_pixels()) {
+                            result.fail(&input, format!("downscale didn't round trip, first difference at pixel {i}"));
+                        }
+                    }
+                }
+                Ok(Err(IndexedImageError::TooBigPostScale(_, _))) if exp_w > 255 || exp_h > 255 => {}
+                Ok(Err(e)) => result.fail(&input, format!("expected {exp_w}x{exp_h}, got error: {e}")),
+                Err(msg) => result.fail(&input, msg),
+            }
+
+            result.cases += 1;
+            let input = format!("{} on Image {w}x{h} (case seed {seed})", name(algo));
+            match catch(|| image.scale(algo)) {
+                Ok(scaled) => {
+                    let actual = (scaled.width(), scaled.height());
+                    if actual != (exp_w, exp_h) {
+                        result.fail(&input, format!("expected {exp_w}x{exp_h}, got {}x{}", actual.0, actual.1));
+                    } else if let Scaling::NearestNeighbour { x_scale, y_scale } = algo {
+                        let (x_scale, y_scale) = (x_scale.get(), y_scale.get());
+                        let restored = downscale_image(&scaled, x_scale, y_scale);
+                        if let Some((x, y)) = first_block_diff(image.pixels(), w as usize, scaled.pixels(), x_scale, y_scale) {
+                            result.fail(&input, format!("pixel {x},{y} differs from the source pixel it was scaled from"));
+                        } else if let Some(i) = first_diff(image.pixels(), restored.pixels()) {
+                            result.fail(&input, format!("downscale didn't round trip, first difference at pixel {i}"));
+                        }
+                    }
+                }
+                Err(msg) => result.fail(&input, msg),
+            }
+        }
+    }
+
+    result
+}
+
+pub fn name(algo: Scaling) -> String {
+    match algo {
+        Scaling::NearestNeighbour { x_scale, y_scale } => format!("nn {x_scale}x{y_scale}"),
+        Scaling::Epx2x => String::from("Epx2x"),
+        Scaling::Epx4x => String::from("Epx4x"),
+    }
+}
+
+pub fn expected_size(width: usize, height: usize, algo: Scaling) -> (usize, usize) {
+    match algo {
+        Scaling::NearestNeighbour { x_scale, y_scale } => (width * x_scale.get(), height * y_scale.get()),
+        Scaling::Epx2x => (width * 2, height * 2),
+        Scaling::Epx4x => (width * 4, height * 4),
+    }
+}
+
+/// Take the top left pixel of every `x_scale` x `y_scale` block, see [first_block_diff] for the rest
+fn downscale_indexed(image: &IndexedImage, x_scale: usize, y_scale: usize) -> IndexedImage {
+    let width = image.width() as usize / x_scale;
+    let height = image.height() as usize / y_scale;
+    let mut pixels = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(image.get_pixels()[x * x_scale + y * y_scale * image.width() as usize]);
+        }
+    }
+    IndexedImage::new(width as u8, height as u8, image.get_palette().to_vec(), pixels)
+        .expect("downscaled to invalid image")
+}
+
+/// Take the top left pixel of every `x_scale` x `y_scale` block, see [first_block_diff] for the rest
+fn downscale_image(image: &Image, x_scale: usize, y_scale: usize) -> Image {
+    let width = image.width() / x_scale;
+    let height = image.height() / y_scale;
+    let mut pixels = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(image.get_pixel(x * x_scale, y * y_scale));
+        }
+    }
+    Image::new(pixels, width, height).expect("downscaled to invalid image")
+}
+
+/// First pixel of `scaled` (as x, y) that doesn't equal the pixel of `source` whose
+/// `x_scale` x `y_scale` block it's in, so every pixel of every block is checked
+fn first_block_diff<T: PartialEq>(source: &[T], source_width: usize, scaled: &[T], x_scale: usize, y_scale: usize) -> Option<(usize, usize)> {
+    let scaled_width = source_width * x_scale;
+    (0..scaled.len())
+        .map(|i| (i % scaled_width, i / scaled_width))
+        .find(|(x, y)| scaled[x + y * scaled_width] != source[x / x_scale + y / y_scale * source_width])
+}
+
+fn first_diff<T: PartialEq>(lhs: &[T], rhs: &[T]) -> Option<usize> {
+    if lhs.len() != rhs.len() {
+        return Some(lhs.len().min(rhs.len()));
+    }
+    lhs.iter().zip(rhs).position(|(l, r)| l != r)
+}
@@ -0,0 +1,112 @@
+use crate::checks::gen::*;
+use crate::checks::{catch, SuiteResult};
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+
+/// Every size up to this in both dimensions is checked
+const EXHAUSTIVE_MAX: usize = 16;
+/// Random larger sizes checked on top of the exhaustive ones
+const RANDOM_SIZES: usize = 30;
+
+type Invariant = fn(&Image) -> Result<(), String>;
+
+const INVARIANTS: [(&str, Invariant); 5] = [
+    ("rotate_cw x4 is identity", |image| {
+        same(image, &image.rotate_cw().rotate_cw().rotate_cw().rotate_cw())
+    }),
+    ("rotate_cw then rotate_ccw is identity", |image| {
+        same(image, &image.rotate_cw().rotate_ccw())
+    }),
+    ("flip_vertical x2 is identity", |image| {
+        let mut flipped = image.clone();
+        flipped.flip_vertical();
+        flipped.flip_vertical();
+        same(image, &flipped)
+    }),
+    ("flip_horizontal x2 is identity", |image| {
+        let mut flipped = image.clone();
+        flipped.flip_horizontal();
+        flipped.flip_horizontal();
+        same(image, &flipped)
+    }),
+    ("rotation swaps width and height", |image| {
+        for (name, rotated) in [("rotate_cw", image.rotate_cw()), ("rotate_ccw", image.rotate_ccw())] {
+            if (rotated.width(), rotated.height()) != (image.height(), image.width()) {
+                return Err(format!(
+                    "{name} gave {}x{}",
+                    rotated.width(),
+                    rotated.height()
+                ));
+            }
+        }
+        Ok(())
+    }),
+];
+
+/// Check rotation and flip invariants of [Image] over many sizes, reporting the smallest failing size
+pub fn run(rng: &mut Rng) -> SuiteResult {
+    let mut result = SuiteResult::default();
+
+    let mut sizes = vec![];
+    for w in 1..=EXHAUSTIVE_MAX {
+        for h in 1..=EXHAUSTIVE_MAX {
+            sizes.push((w, h));
+        }
+    }
+    for _ in 0..RANDOM_SIZES {
+        sizes.push((rng.usize(1..=300), rng.usize(1..=300)));
+    }
+    sizes.sort_by_key(|(w, h)| (w * h, *w));
+
+    for (name, invariant) in INVARIANTS {
+        let mut smallest = None;
+        let mut failed = 0;
+        for (w, h) in &sizes {
+            result.cases += 1;
+            let (seed, mut case_rng) = case_rng(rng);
+            let image = image(&mut case_rng, *w, *h);
+            let outcome = catch(|| invariant(&image)).and_then(|r| r);
+            if let Err(msg) = outcome {
+                failed += 1;
+                if smallest.is_none() {
+                    smallest = Some((*w, *h, seed, msg));
+                }
+            }
+        }
+        if let Some((w, h, seed, msg)) = smallest {
+            result.fail(
+                format!("{name}: smallest failing size {w}x{h} (case seed {seed}, {failed} of {} sizes failed)", sizes.len()),
+                msg,
+            );
+        }
+    }
+
+    result
+}
+
+fn same(expected: &Image, actual: &Image) -> Result<(), String> {
+    if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+        return Err(format!(
+            "size changed from {}x{} to {}x{}",
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height()
+        ));
+    }
+    match expected
+        .pixels()
+        .iter()
+        .zip(actual.pixels())
+        .position(|(e, a)| e != a)
+    {
+        Some(i) => Err(format!(
+            "pixel ({},{}) was {} now {}",
+            i % expected.width(),
+            i / expected.width(),
+            expected.pixels()[i].to_hex(),
+            actual.pixels()[i].to_hex()
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,120 @@
+use crate::checks::gen::*;
+use crate::checks::{catch, SuiteResult};
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+
+type Checked = fn(&IndexedImage) -> Result<IndexedImage, IndexedImageError>;
+type Unchecked = unsafe fn(&IndexedImage) -> IndexedImage;
+
+const PAIRS: [(&str, Checked, Unchecked); 8] = [
+    (
+        "flip_vertical",
+        |img| img.flip_vertical(),
+        |img| unsafe { img.flip_vertical_unchecked() },
+    ),
+    (
+        "flip_horizontal",
+        |img| img.flip_horizontal(),
+        |img| unsafe { img.flip_horizontal_unchecked() },
+    ),
+    (
+        "rotate_cw",
+        |img| Ok(img.rotate_cw()),
+        |img| unsafe { img.rotate_cw_unchecked() },
+    ),
+    (
+        "rotate_ccw",
+        |img| Ok(img.rotate_ccw()),
+        |img| unsafe { img.rotate_ccw_unchecked() },
+    ),
+    (
+        "scale(nn_double)",
+        |img| img.scale(Scaling::nn_double()),
+        |img| unsafe { img.scale_unchecked(Scaling::nn_double()) },
+    ),
+    (
+        "scale(nn 3x1)",
+        |img| img.scale(Scaling::nearest_neighbour(3, 1).unwrap()),
+        |img| unsafe { img.scale_unchecked(Scaling::nearest_neighbour(3, 1).unwrap()) },
+    ),
+    (
+        "scale(Epx2x)",
+        |img| img.scale(Scaling::Epx2x),
+        |img| unsafe { img.scale_unchecked(Scaling::Epx2x) },
+    ),
+    (
+        "scale(Epx4x)",
+        |img| img.scale(Scaling::Epx4x),
+        |img| unsafe { img.scale_unchecked(Scaling::Epx4x) },
+    ),
+];
+
+/// Random sizes generated per palette size on top of [EDGE_SIZES]
+const RANDOM_SIZES: usize = 2;
+
+/// Compare every checked IndexedImage method against its `_unchecked` twin
+pub fn run(rng: &mut Rng) -> SuiteResult {
+    let mut result = SuiteResult::default();
+
+    let mut inputs = vec![];
+    for palette_len in 1..=256 {
+        for (w, h) in EDGE_SIZES {
+            inputs.push((w, h, palette_len));
+        }
+        for _ in 0..RANDOM_SIZES {
+            inputs.push((rng.u8(1..), rng.u8(1..), palette_len));
+        }
+    }
+
+    for (w, h, palette_len) in inputs {
+        let (seed, mut case_rng) = case_rng(rng);
+        let image = indexed_image(&mut case_rng, w, h, palette_len);
+        for (name, checked, unchecked) in PAIRS {
+            result.cases += 1;
+            let input = format!("{name} on {} (case seed {seed})", describe_indexed(&image));
+            if let Err(msg) = compare(&image, checked, unchecked) {
+                result.fail(input, msg);
+            }
+        }
+    }
+
+    result
+}
+
+fn compare(image: &IndexedImage, checked: Checked, unchecked: Unchecked) -> Result<(), String> {
+    let expected = match catch(|| checked(image)) {
+        Ok(Ok(img)) => img,
+        // the unchecked version isn't expected to handle this, so there's nothing to compare
+        Ok(Err(IndexedImageError::TooBigPostScale(_, _))) => return Ok(()),
+        Ok(Err(e)) => return Err(format!("checked returned error: {e}")),
+        Err(msg) => return Err(format!("checked {msg}")),
+    };
+    let actual = catch(|| unsafe { unchecked(image) }).map_err(|msg| format!("unchecked {msg}"))?;
+
+    if expected.size() != actual.size() {
+        return Err(format!(
+            "size differs, checked: {:?} unchecked: {:?}",
+            expected.size(),
+            actual.size()
+        ));
+    }
+    if expected.get_palette() != actual.get_palette() {
+        return Err(String::from("palette differs"));
+    }
+    if let Some(i) = expected
+        .get_pixels()
+        .iter()
+        .zip(actual.get_pixels())
+        .position(|(e, a)| e != a)
+    {
+        let w = expected.width() as usize;
+        return Err(format!(
+            "pixel {i} ({},{}) differs, checked: {} unchecked: {}",
+            i % w,
+            i / w,
+            expected.get_pixels()[i],
+            actual.get_pixels()[i]
+        ));
+    }
+    Ok(())
+}
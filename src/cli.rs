@@ -0,0 +1,269 @@
+//! Command line flags shared by every command, and the commands that list and render tests
+//!
+//! Tests can be picked by number, by name (the title without its number, i.e. `standard 4x4` or
+//! `standard_4x4`), by category or tag, or with `all`.
+
+use crate::category::{self, Category};
+use crate::checks;
+use crate::{capture, Example, SCREEN_HEIGHT, SCREEN_WIDTH};
+use anyhow::{anyhow, bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use std::fs;
+use std::path::Path;
+
+pub const DEFAULT_RENDER_DIR: &str = "renders";
+
+pub const USAGE: &str = "Usage: graphics_tester [COMMAND] [--scale N] [--size WxH] [--seed N]
+                      [--control stdin|SOCKET]
+
+Commands:
+  (none)                    Open the window at the last viewed test
+  show <test>               Open the window at <test>
+  list                      Print every test's number, category and name
+  render <tests> [--out DIR] [--time SECONDS]
+                            Render tests to PNGs (default renders/)
+  check [--baselines DIR] [--out DIR] [--update] [--junit FILE] [--include NAMES] [--exclude NAMES]
+                            Compare renders against baselines
+  bench [--iterations N] [--out FILE] [--compare FILE] [--threshold PERCENT]
+  verify [suite] [--all]    Run the invariant suites
+  fuzz [--cases N]          Fuzz the drawing API
+  record <test> [--frames N] [--fps N] [--format gif|apng|ica] [--out DIR]
+  replay <file> [--window] [--out PNG]
+  validate-assets           Load every asset and scene file
+
+<test> is a number or name, <tests> can also be a category, a tag or `all`.
+
+Common flags (an error for commands that don't use them):
+  --scale N      Window scale (1, 2 or 4) or output scale for render and record
+  --size WxH     Canvas size for the windows, render, record, bench and check,
+                 tests are laid out for 250x250
+  --seed N       Seed for verify, fuzz and the invariants run by check --junit
+  --control X    Accept commands for the window from stdin or a Unix socket at X
+";
+
+/// Flags accepted anywhere on the command line, see [Common::only]
+#[derive(Debug, Clone)]
+pub struct Common {
+    pub scale: usize,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    /// `stdin` or a socket path, see [crate::control]
+    pub control: Option<String>,
+    /// Flags that were given, so commands can reject those they don't use
+    given: Vec<&'static str>,
+}
+
+impl Default for Common {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            width: SCREEN_WIDTH as usize,
+            height: SCREEN_HEIGHT as usize,
+            seed: checks::DEFAULT_SEED,
+            control: None,
+            given: vec![],
+        }
+    }
+}
+
+impl Common {
+    /// Remove the common flags from `args`, returning them and the remaining args
+    pub fn extract(args: Vec<String>) -> Result<(Common, Vec<String>)> {
+        let mut common = Common::default();
+        let mut rest = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!("{arg} requires a value"));
+            let flag = match arg.as_str() {
+                "--scale" => {
+                    common.scale = value()?.parse()?;
+                    if common.scale == 0 {
+                        bail!("--scale must be at least 1");
+                    }
+                    "--scale"
+                }
+                "--size" => {
+                    (common.width, common.height) = parse_size(&value()?)?;
+                    "--size"
+                }
+                "--seed" => {
+                    common.seed = value()?.parse()?;
+                    "--seed"
+                }
+                "--control" => {
+                    common.control = Some(value()?);
+                    "--control"
+                }
+                _ => {
+                    rest.push(arg);
+                    continue;
+                }
+            };
+            common.given.push(flag);
+        }
+        Ok((common, rest))
+    }
+
+    /// Fail if any common flag other than `used` was given, rather than silently ignoring it
+    pub fn only(&self, command: &str, used: &[&str]) -> Result<()> {
+        match self.given.iter().find(|flag| !used.contains(flag)) {
+            Some(flag) => bail!("{command} doesn't use {flag}"),
+            None => Ok(()),
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn window_options(&self) -> Result<Options> {
+        let scaling = match self.scale {
+            1 => WindowScaling::Native,
+            2 => WindowScaling::Double,
+            4 => WindowScaling::Quad,
+            n => bail!("window scale must be 1, 2 or 4, not {n}"),
+        };
+        Ok(Options {
+            scaling,
+            ..Options::default()
+        })
+    }
+}
+
+/// Parse `WxH`, i.e. `320x240`
+fn parse_size(text: &str) -> Result<(usize, usize)> {
+    let (width, height) = text
+        .split_once('x')
+        .ok_or_else(|| anyhow!("--size should be WIDTHxHEIGHT, not {text}"))?;
+    let size = (width.parse()?, height.parse()?);
+    if size.0 == 0 || size.1 == 0 {
+        bail!("--size must be at least 1x1");
+    }
+    Ok(size)
+}
+
+/// Title of every test, found by rendering each one offscreen
+pub fn titles(example: &mut Example) -> Result<Vec<String>> {
+    let mut titles = vec![];
+    let size = (SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize);
+    example.render_tests(0..example.test_count(), size, None, |rendered| {
+        titles.push(rendered.title);
+        Ok(())
+    })?;
+    example.current_test = 0;
+    Ok(titles)
+}
+
+/// `title` without the test number, i.e. "Standard 4x4" for "45) Standard 4x4"
+fn name(title: &str) -> &str {
+    match title.split_once(") ") {
+        Some((number, name)) if number.parse::<usize>().is_ok() => name,
+        _ => title,
+    }
+}
+
+/// Lowercase with anything other than letters and digits replaced with `_`
+fn normalise(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Tests picked by `spec`: a number, `all`, a category or tag, or a name
+///
+/// A name matches if it's the whole name or, if no name matches exactly, part of it.
+pub fn select(spec: &str, titles: &[String]) -> Result<Vec<usize>> {
+    if let Ok(test) = spec.parse::<usize>() {
+        if test >= titles.len() {
+            bail!("there are only {} tests", titles.len());
+        }
+        return Ok(vec![test]);
+    }
+    let all = 0..titles.len();
+    let spec = normalise(spec);
+    if spec == "all" {
+        return Ok(all.collect());
+    }
+    if Category::ALL.iter().any(|c| c.name() == spec) || category::TAGS.contains(&spec.as_str()) {
+        return Ok(all.filter(|test| category::has(*test, &spec)).collect());
+    }
+    let names: Vec<String> = titles.iter().map(|title| normalise(name(title))).collect();
+    let exact: Vec<usize> = all.clone().filter(|test| names[*test] == spec).collect();
+    if !exact.is_empty() {
+        return Ok(exact);
+    }
+    let partial: Vec<usize> = all.filter(|test| names[*test].contains(&spec)).collect();
+    if partial.is_empty() {
+        bail!("no test matches '{spec}', see `list`");
+    }
+    Ok(partial)
+}
+
+/// A single test picked by `spec`, failing if it matches more than one
+pub fn select_one(spec: &str, titles: &[String]) -> Result<usize> {
+    match select(spec, titles)?.as_slice() {
+        [test] => Ok(*test),
+        tests => {
+            let matches: Vec<&str> = tests.iter().map(|test| titles[*test].as_str()).collect();
+            bail!(
+                "'{spec}' matches {} tests: {}",
+                tests.len(),
+                matches.join(", ")
+            )
+        }
+    }
+}
+
+pub fn list() -> Result<()> {
+    let mut example = Example::new();
+    for (test, title) in titles(&mut example)?.iter().enumerate() {
+        println!(
+            "{test:>3}  {:<12} {}",
+            Category::of(test).name(),
+            name(title)
+        );
+    }
+    Ok(())
+}
+
+/// Render the tests picked by `spec` at `seconds` into `output`, reporting any that panic and
+/// carrying on with the rest
+pub fn render(common: &Common, spec: &str, seconds: f64, output: &Path) -> Result<()> {
+    let mut example = Example::new();
+    let titles = titles(&mut example)?;
+    let tests = select(spec, &titles)?;
+    fs::create_dir_all(output).with_context(|| format!("creating {}", output.display()))?;
+
+    let scaling = Scaling::nearest_neighbour(common.scale, common.scale)
+        .map_err(|e| anyhow!("invalid scale {}: {e:?}", common.scale))?;
+    let mut panicked = 0;
+    example.render_tests(tests, common.size(), Some(seconds), |rendered| {
+        let mut image = match rendered.image {
+            Ok(image) => image,
+            Err(message) => {
+                panicked += 1;
+                eprintln!("{}: {message}", rendered.title);
+                return Ok(());
+            }
+        };
+        if common.scale > 1 {
+            image = image.scale(scaling);
+        }
+        let stem = capture::file_stem(rendered.test, &titles[rendered.test]);
+        let path = output.join(format!("{stem}.png"));
+        capture::save_png(&path, &image)?;
+        println!("{}", path.display());
+        Ok(())
+    })?;
+    if panicked > 0 {
+        bail!("{panicked} test(s) panicked");
+    }
+    Ok(())
+}
@@ -0,0 +1,247 @@
+//! Side by side comparison of two frames at 1:1
+//!
+//! Each half of the window shows the same columns of its frame (scroll to pan when the window is
+//! narrower than two frames, or run with `--size 500x250` to see both whole). Moving the mouse
+//! over either half marks the same pixel in both and shows a loupe of each.
+
+use crate::control::status;
+use crate::{overlay, SCREEN_WIDTH};
+use pixels_graphics_lib::prelude::*;
+
+const HEADER_HEIGHT: isize = 16;
+/// Pixels panned per scroll step
+const PAN_STEP: isize = 10;
+/// Step between animation times in [Mode::Times], one frame at 60fps
+pub const TIME_STEP: f64 = 1.0 / 60.0;
+/// Size of [overlay::draw_magnified] including its label
+const LOUPE_WIDTH: isize = 90;
+const LOUPE_HEIGHT: isize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The current test beside another test, both animating
+    Tests,
+    /// The current test at the start of its animation beside a later time
+    Times,
+    /// The current test at the start of its animation beside its snapshot baseline
+    Baseline,
+}
+
+impl Mode {
+    fn next(&self) -> Mode {
+        match self {
+            Mode::Tests => Mode::Times,
+            Mode::Times => Mode::Baseline,
+            Mode::Baseline => Mode::Tests,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Tests => "two tests",
+            Mode::Times => "two times",
+            Mode::Baseline => "render vs baseline",
+        }
+    }
+}
+
+/// A frame to show in one half
+pub struct Side {
+    pub label: String,
+    /// None if it couldn't be made, i.e. there's no baseline
+    pub frame: Option<Image>,
+}
+
+pub struct Compare {
+    pub open: bool,
+    pub mode: Mode,
+    /// Right hand test in [Mode::Tests]
+    pub other_test: usize,
+    /// Right hand time in [Mode::Times], in seconds
+    pub other_time: f64,
+    /// First column shown of each frame, limited when drawn so the frames fill their halves
+    pan: isize,
+    /// Frames for [Mode::Times] and [Mode::Baseline], which don't change until a setting does,
+    /// and the test, mode and time they were made for
+    cache: Option<((usize, Mode, f64), Side, Side)>,
+}
+
+impl Default for Compare {
+    fn default() -> Self {
+        Self {
+            open: false,
+            mode: Mode::Tests,
+            other_test: 1,
+            other_time: 0.5,
+            pan: 0,
+            cache: None,
+        }
+    }
+}
+
+impl Compare {
+    pub fn next_mode(&mut self) {
+        self.mode = self.mode.next();
+        status!("Comparing {}", self.mode.name());
+    }
+
+    /// Change the right hand test or time
+    pub fn step_other(&mut self, forward: bool, test_count: usize) {
+        match self.mode {
+            Mode::Tests => {
+                self.other_test = if forward {
+                    (self.other_test + 1).min(test_count - 1)
+                } else {
+                    self.other_test.saturating_sub(1)
+                }
+            }
+            Mode::Times => {
+                let step = if forward { TIME_STEP } else { -TIME_STEP };
+                self.other_time = (self.other_time + step).max(0.0);
+            }
+            Mode::Baseline => {}
+        }
+    }
+
+    pub fn pan_by(&mut self, steps: isize) {
+        self.pan = (self.pan + steps * PAN_STEP).clamp(0, SCREEN_WIDTH);
+    }
+
+    /// Cached frames for `test` at `time`, if they're still valid
+    pub fn cached(&self, test: usize, time: f64) -> Option<(&Side, &Side)> {
+        match &self.cache {
+            Some((key, left, right)) if *key == (test, self.mode, time) => Some((left, right)),
+            _ => None,
+        }
+    }
+
+    pub fn cache(&mut self, test: usize, time: f64, left: Side, right: Side) {
+        self.cache = Some(((test, self.mode, time), left, right));
+    }
+
+    /// Drop any cached frames, i.e. when a baseline might have changed
+    pub fn clear_cache(&mut self) {
+        self.cache = None;
+    }
+
+    pub fn render(&self, graphics: &mut Graphics, left: &Side, right: &Side, mouse: Coord) {
+        let width = graphics.width() as isize;
+        let height = graphics.height() as isize;
+        let half = width / 2;
+        let frame_width = [left, right]
+            .iter()
+            .filter_map(|side| side.frame.as_ref())
+            .map(|frame| frame.width() as isize)
+            .max()
+            .unwrap_or(SCREEN_WIDTH);
+        let pan = self.pan.min((frame_width - half).max(0));
+        graphics.clear(BLACK);
+        for (side, x) in [(left, 0), (right, half)] {
+            match &side.frame {
+                Some(frame) => {
+                    if let Some(visible) = crop(frame, pan, half) {
+                        graphics.draw_image(coord!(x, 0), &visible);
+                    }
+                }
+                None => graphics.draw_text(
+                    "Nothing to show",
+                    TextPos::px(coord!(x + half / 2, height / 2)),
+                    TextFormat::from((MID_GRAY, PixelFont::Standard4x5, Positioning::Center)),
+                ),
+            }
+        }
+
+        graphics.draw_rect(Rect::new((0, 0), (width, HEADER_HEIGHT)), fill(BLACK));
+        graphics.draw_line((half, 0), (half, height), WHITE);
+        let font = PixelFont::Standard4x5;
+        let chars = (half as usize - 4) / (font.char_width() + 1);
+        for (side, x) in [(left, 0), (right, half)] {
+            let label: String = side.label.chars().take(chars).collect();
+            graphics.draw_text(&label, TextPos::px(coord!(x + 2, 2)), (WHITE, font));
+        }
+        let summary = match (&left.frame, &right.frame) {
+            (Some(a), Some(b)) => match differences(a, b) {
+                Some(0) => String::from("identical"),
+                Some(count) => format!("{count} pixels differ"),
+                None => format!(
+                    "sizes differ ({}x{} vs {}x{})",
+                    a.width(),
+                    a.height(),
+                    b.width(),
+                    b.height()
+                ),
+            },
+            _ => String::new(),
+        };
+        graphics.draw_text(
+            &format!("{}: {summary}", self.mode.name()),
+            TextPos::px(coord!(2, 9)),
+            (YELLOW, font),
+        );
+
+        if mouse.y < HEADER_HEIGHT || mouse.x < 0 || mouse.x >= width {
+            return;
+        }
+        let local_x = if mouse.x < half {
+            mouse.x
+        } else {
+            mouse.x - half
+        };
+        let point = coord!(local_x + pan, mouse.y);
+        let loupe_y = if mouse.y < height / 2 {
+            height - LOUPE_HEIGHT
+        } else {
+            HEADER_HEIGHT + 2
+        };
+        for (side, x) in [(left, 0), (right, half)] {
+            draw_marker(graphics, coord!(x + local_x, mouse.y), x, half);
+            if let Some(frame) = &side.frame {
+                let origin = coord!(x + (half - LOUPE_WIDTH) / 2, loupe_y);
+                overlay::draw_magnified(graphics, frame, point, origin);
+            }
+        }
+    }
+}
+
+/// Cross at `xy`, kept within the half starting at `left`
+fn draw_marker(graphics: &mut Graphics, xy: Coord, left: isize, half: isize) {
+    let color = Color::new(255, 255, 0, 160);
+    graphics.draw_line(
+        ((xy.x - 3).max(left), xy.y),
+        ((xy.x + 3).min(left + half - 1), xy.y),
+        color,
+    );
+    graphics.draw_line((xy.x, xy.y - 3), (xy.x, xy.y + 3), color);
+}
+
+/// `width` columns of `image` starting at `x`, or None if there aren't any
+fn crop(image: &Image, x: isize, width: isize) -> Option<Image> {
+    let x = x.max(0) as usize;
+    let width = (width.max(0) as usize).min(image.width().saturating_sub(x));
+    if width == 0 {
+        return None;
+    }
+    let mut pixels = Vec::with_capacity(width * image.height());
+    for y in 0..image.height() {
+        for dx in 0..width {
+            pixels.push(image.get_pixel(x + dx, y));
+        }
+    }
+    Image::new(pixels, width, image.height()).ok()
+}
+
+/// Number of pixels that differ, or None if the sizes don't match
+fn differences(a: &Image, b: &Image) -> Option<usize> {
+    if a.width() != b.width() || a.height() != b.height() {
+        return None;
+    }
+    let mut count = 0;
+    for y in 0..a.height() {
+        for x in 0..a.width() {
+            if a.get_pixel(x, y) != b.get_pixel(x, y) {
+                count += 1;
+            }
+        }
+    }
+    Some(count)
+}
@@ -0,0 +1,232 @@
+//! Line based control channel for driving the window from scripts
+//!
+//! Started with `--control stdin` or `--control PATH` (a Unix socket), every line is a command
+//! and gets a single line reply, `ok [detail]` or `error <message>`:
+//!
+//! - `goto <test>` - show a test
+//! - `set-time <seconds>` - set the animations to `seconds` after the start and hold them there
+//! - `resume` - let the animations run again
+//! - `mouse <x> <y>` - move the mouse
+//! - `key <name>` - press and release a key, named as in [crate::bindings]
+//! - `screenshot <file.png>` - save the next frame, replying once it's written
+//! - `status` - the current test's title, i.e. `33) Clipping (complex)`
+//! - `quit` - close the window
+//!
+//! Commands are run at the start of the next update, one connection at a time.
+//!
+//! With `--control stdin` stdout only carries replies, the window's own messages (i.e. `Saved
+//! screenshots/...`) are printed to stderr instead, see [status].
+
+use crate::input::parse_key;
+use anyhow::Result;
+use pixels_graphics_lib::prelude::*;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+/// Set while replies are written to stdout
+static REPLYING_ON_STDOUT: AtomicBool = AtomicBool::new(false);
+
+pub fn replying_on_stdout() -> bool {
+    REPLYING_ON_STDOUT.load(Ordering::Relaxed)
+}
+
+/// `println!` for messages from the window, which go to stderr while replies are written to
+/// stdout so scripts only read replies there
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::control::replying_on_stdout() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+pub(crate) use status;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Goto(usize),
+    SetTime(f64),
+    Resume,
+    Mouse(isize, isize),
+    Key(KeyCode),
+    Screenshot(PathBuf),
+    Status,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let command = match parts.as_slice() {
+            ["goto", test] => Command::Goto(number(test)?),
+            ["set-time", seconds] => {
+                let seconds: f64 = seconds
+                    .parse()
+                    .map_err(|_| format!("invalid time {seconds}"))?;
+                if !seconds.is_finite() || seconds < 0.0 {
+                    return Err(format!("invalid time {seconds}"));
+                }
+                Command::SetTime(seconds)
+            }
+            ["resume"] => Command::Resume,
+            ["mouse", x, y] => Command::Mouse(number(x)?, number(y)?),
+            ["key", name] => {
+                Command::Key(parse_key(name).ok_or_else(|| format!("unknown key {name}"))?)
+            }
+            ["screenshot", path] => Command::Screenshot(PathBuf::from(path)),
+            ["status"] => Command::Status,
+            ["quit"] => Command::Quit,
+            [] => return Err(String::from("empty command")),
+            [name, ..] => return Err(format!("unknown command or wrong arguments: {name}")),
+        };
+        Ok(command)
+    }
+}
+
+fn number<T: FromStr>(text: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("invalid number {text}"))
+}
+
+/// A command waiting to be run by the window
+pub struct Request {
+    pub command: Command,
+    reply: Sender<String>,
+}
+
+impl Request {
+    pub fn reply(self, result: Result<String, String>) {
+        let line = match result {
+            Ok(detail) if detail.is_empty() => String::from("ok"),
+            Ok(detail) => format!("ok {detail}"),
+            Err(message) => format!("error {message}"),
+        };
+        //the connection may have closed, nothing to do about it
+        let _ = self.reply.send(line);
+    }
+}
+
+pub struct Control {
+    requests: Receiver<Request>,
+    /// Socket file to remove when the window closes
+    socket: Option<PathBuf>,
+}
+
+impl Control {
+    /// `stdin` or the path of a Unix socket to create
+    pub fn open(target: &str) -> Result<Control> {
+        if target == "stdin" {
+            Ok(Control::stdin())
+        } else {
+            Control::socket(PathBuf::from(target))
+        }
+    }
+
+    fn stdin() -> Control {
+        REPLYING_ON_STDOUT.store(true, Ordering::Relaxed);
+        let (sender, requests) = channel();
+        thread::spawn(move || {
+            let stdin = std::io::stdin();
+            serve(stdin.lock(), std::io::stdout(), &sender);
+        });
+        Control {
+            requests,
+            socket: None,
+        }
+    }
+
+    #[cfg(unix)]
+    fn socket(path: PathBuf) -> Result<Control> {
+        use anyhow::{bail, Context};
+        use std::io::BufReader;
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixListener;
+
+        //a socket left by a window that didn't close cleanly, but never anything else
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                bail!("{} already exists and isn't a socket", path.display());
+            }
+            std::fs::remove_file(&path)
+                .with_context(|| format!("removing old socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("creating socket {}", path.display()))?;
+        println!("Listening for commands on {}", path.display());
+        let (sender, requests) = channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let Ok(reader) = stream.try_clone() else {
+                    continue;
+                };
+                if !serve(BufReader::new(reader), stream, &sender) {
+                    break;
+                }
+            }
+        });
+        Ok(Control {
+            requests,
+            socket: Some(path),
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn socket(_: PathBuf) -> Result<Control> {
+        anyhow::bail!("control sockets are only supported on Unix, use --control stdin")
+    }
+
+    /// Commands received since the last call
+    pub fn pending(&self) -> Vec<Request> {
+        self.requests.try_iter().collect()
+    }
+}
+
+impl Drop for Control {
+    fn drop(&mut self) {
+        match &self.socket {
+            Some(path) => {
+                let _ = std::fs::remove_file(path);
+            }
+            None => REPLYING_ON_STDOUT.store(false, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Pass each line of `input` to the window and write its reply to `output`, returns false once
+/// the window has closed
+fn serve(input: impl BufRead, mut output: impl Write, sender: &Sender<Request>) -> bool {
+    for line in input.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let reply = match line.parse::<Command>() {
+            Ok(command) => {
+                let (reply, receiver) = channel();
+                if sender.send(Request { command, reply }).is_err() {
+                    return false;
+                }
+                match receiver.recv() {
+                    Ok(reply) => reply,
+                    Err(_) => return false,
+                }
+            }
+            Err(message) => format!("error {message}"),
+        };
+        if writeln!(output, "{reply}")
+            .and_then(|_| output.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+    true
+}
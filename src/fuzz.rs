@@ -0,0 +1,759 @@
+//! Seeded fuzzing of the draw calls used by the tests
+//!
+//! Run with `graphics_tester fuzz [--seed N] [--cases N]`, the same seed always generates the same
+//! cases. Any case that panics is shrunk to the smallest list of draws that still panics the same
+//! way and printed as a test function.
+
+use crate::checks::catch;
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use anyhow::{bail, Result};
+use fastrand::Rng;
+use pixels_graphics_lib::prelude::*;
+use std::fmt::Write;
+use std::panic;
+
+pub const DEFAULT_CASES: usize = 500;
+const MAX_OPS: usize = 12;
+/// Max distance between points of a shape, as every filled pixel is calculated up front
+const MAX_EXTENT: isize = 600;
+/// Lines only store their outline so can cross the whole screen from far away
+const MAX_LINE_LENGTH: isize = 100_000;
+const TEXT_CHARS: &str = "abcXYZ019 !?.,\n\t…£✓€|";
+
+#[derive(Debug, Clone)]
+enum ShapeDef {
+    Line(Coord, Coord),
+    Rect(Coord, Coord),
+    Circle(Coord, usize),
+    Ellipse(Coord, usize, usize),
+    Triangle(Coord, Coord, Coord),
+    Polygon(Vec<Coord>),
+}
+
+#[derive(Debug, Clone)]
+enum Transform {
+    Rotation(isize),
+    RotationAround(isize, Coord),
+    Scale(f32),
+    Move(Coord),
+    Translation(Coord),
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Drawable {
+        shape: ShapeDef,
+        draw_type: DrawType,
+        transforms: Vec<Transform>,
+        offset: Option<Coord>,
+    },
+    Collection {
+        shapes: Vec<(ShapeDef, DrawType)>,
+        transforms: Vec<Transform>,
+    },
+    Text {
+        text: String,
+        pos: Coord,
+        color: Color,
+        font: PixelFont,
+        wrapping: WrappingStrategy,
+        positioning: Positioning,
+    },
+    IndexedImage {
+        pos: Coord,
+        width: u8,
+        height: u8,
+        colors: u8,
+    },
+    Image {
+        pos: Coord,
+        width: usize,
+        height: usize,
+    },
+}
+
+pub fn run(seed: u64, cases: usize) -> Result<()> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut rng = Rng::with_seed(seed);
+    let mut failed = 0;
+    for case in 0..cases {
+        let case_seed = rng.u64(..);
+        let ops = generate(&mut Rng::with_seed(case_seed));
+        if let Err(msg) = render(&ops) {
+            failed += 1;
+            let minimal = shrink(ops.clone(), &signature(&msg));
+            println!("[FAIL] case {case} (case seed {case_seed}): {msg}");
+            println!(
+                "  shrunk from {} to {} draws:\n\n{}",
+                ops.len(),
+                minimal.len(),
+                to_test_fn(&minimal, case_seed)
+            );
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    if failed > 0 {
+        bail!("{failed} of {cases} cases failed (seed {seed})");
+    }
+    println!("[PASS] {cases} cases (seed {seed})");
+    Ok(())
+}
+
+/// Draw every op into a fresh screen sized buffer
+fn render(ops: &[Op]) -> Result<(), String> {
+    let mut buffer = Graphics::create_buffer_u8(SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize);
+    let mut graphics =
+        Graphics::new_u8_rgba(&mut buffer, SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize)
+            .map_err(|e| e.to_string())?;
+    for op in ops {
+        catch(panic::AssertUnwindSafe(|| op.draw(&mut graphics)))?;
+    }
+    Ok(())
+}
+
+/// Panic message with numbers removed, so shrinking keeps the same failure even when
+/// indices or lengths in the message change
+fn signature(msg: &str) -> String {
+    msg.chars()
+        .map(|c| if c.is_ascii_digit() { '#' } else { c })
+        .collect()
+}
+
+fn shrink(mut ops: Vec<Op>, signature: &str) -> Vec<Op> {
+    let still_fails = |ops: &[Op]| {
+        render(ops)
+            .err()
+            .map(|msg| self::signature(&msg) == signature)
+            .unwrap_or(false)
+    };
+
+    loop {
+        let mut changed = false;
+
+        let mut i = 0;
+        while i < ops.len() {
+            let mut candidate = ops.clone();
+            candidate.remove(i);
+            if still_fails(&candidate) {
+                ops = candidate;
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        for i in 0..ops.len() {
+            for simpler in ops[i].simplified() {
+                let mut candidate = ops.clone();
+                candidate[i] = simpler;
+                if still_fails(&candidate) {
+                    ops = candidate;
+                    changed = true;
+                    break;
+                }
+            }
+        }
+
+        if !changed {
+            return ops;
+        }
+    }
+}
+
+fn generate(rng: &mut Rng) -> Vec<Op> {
+    (0..rng.usize(1..=MAX_OPS)).map(|_| gen_op(rng)).collect()
+}
+
+/// Mostly on screen, but also just off screen, far off screen and at the limits of i32
+///
+/// Transforms go through f32, so beyond i32 a shape can grow by thousands of pixels per side and
+/// the rasteriser tries to allocate gigabytes, which aborts rather than panics, so those values
+/// aren't generated
+fn gen_value(rng: &mut Rng) -> isize {
+    let sign = if rng.bool() { 1 } else { -1 };
+    match rng.u8(..10) {
+        0..=4 => rng.isize(0..=SCREEN_WIDTH),
+        5 | 6 => rng.isize(-1000..=SCREEN_WIDTH + 1000),
+        7 => sign * rng.isize(10_000..1_000_000),
+        8 => sign * rng.isize(1_000_000..1_000_000_000),
+        _ => [i32::MIN as isize, i32::MAX as isize][rng.usize(..2)],
+    }
+}
+
+fn gen_coord(rng: &mut Rng) -> Coord {
+    Coord::new(gen_value(rng), gen_value(rng))
+}
+
+fn gen_near(rng: &mut Rng, origin: Coord, extent: isize) -> Coord {
+    origin + (rng.isize(-extent..=extent), rng.isize(-extent..=extent))
+}
+
+fn gen_color(rng: &mut Rng) -> Color {
+    let alpha = [0, 1, 128, 255, 255, 255][rng.usize(..6)];
+    Color::new(rng.u8(..), rng.u8(..), rng.u8(..), alpha)
+}
+
+fn gen_draw_type(rng: &mut Rng) -> DrawType {
+    if rng.bool() {
+        fill(gen_color(rng))
+    } else {
+        stroke(gen_color(rng))
+    }
+}
+
+fn gen_shape(rng: &mut Rng) -> ShapeDef {
+    let origin = gen_coord(rng);
+    match rng.u8(..6) {
+        0 => ShapeDef::Line(origin, gen_near(rng, origin, MAX_LINE_LENGTH)),
+        1 => ShapeDef::Rect(origin, gen_near(rng, origin, MAX_EXTENT)),
+        2 => ShapeDef::Circle(origin, rng.usize(0..=MAX_EXTENT as usize / 2)),
+        3 => ShapeDef::Ellipse(
+            origin,
+            rng.usize(0..=MAX_EXTENT as usize / 2),
+            rng.usize(0..=MAX_EXTENT as usize / 2),
+        ),
+        4 => ShapeDef::Triangle(
+            origin,
+            gen_near(rng, origin, MAX_EXTENT),
+            gen_near(rng, origin, MAX_EXTENT),
+        ),
+        _ => ShapeDef::Polygon(
+            (0..if rng.u8(..20) == 0 {
+                0
+            } else {
+                rng.usize(1..8)
+            })
+                .map(|_| gen_near(rng, origin, MAX_EXTENT))
+                .collect(),
+        ),
+    }
+}
+
+fn gen_transforms(rng: &mut Rng) -> Vec<Transform> {
+    (0..rng.usize(0..3))
+        .map(|_| match rng.u8(..5) {
+            0 => Transform::Rotation(rng.isize(-720..=720)),
+            1 => Transform::RotationAround(rng.isize(-720..=720), gen_coord(rng)),
+            2 => Transform::Scale([0.0, 0.1, 0.5, 1.5, 3.0, -1.0][rng.usize(..6)]),
+            3 => Transform::Move(gen_coord(rng)),
+            _ => Transform::Translation(gen_coord(rng)),
+        })
+        .collect()
+}
+
+fn gen_op(rng: &mut Rng) -> Op {
+    match rng.u8(..10) {
+        0..=3 => Op::Drawable {
+            shape: gen_shape(rng),
+            draw_type: gen_draw_type(rng),
+            transforms: gen_transforms(rng),
+            offset: if rng.bool() {
+                Some(gen_coord(rng))
+            } else {
+                None
+            },
+        },
+        4 | 5 => Op::Collection {
+            shapes: (0..rng.usize(0..5))
+                .map(|_| (gen_shape(rng), gen_draw_type(rng)))
+                .collect(),
+            transforms: gen_transforms(rng),
+        },
+        6 | 7 => {
+            let chars: Vec<char> = TEXT_CHARS.chars().collect();
+            let col = rng.usize(0..40);
+            Op::Text {
+                text: (0..rng.usize(0..60))
+                    .map(|_| chars[rng.usize(..chars.len())])
+                    .collect(),
+                pos: gen_coord(rng),
+                color: gen_color(rng),
+                font: [
+                    PixelFont::Outline7x9,
+                    PixelFont::Standard4x4,
+                    PixelFont::Standard4x5,
+                    PixelFont::Standard6x7,
+                    PixelFont::Standard8x10,
+                    PixelFont::Script8x8,
+                    PixelFont::Limited3x5,
+                ][rng.usize(..7)],
+                wrapping: [
+                    WrappingStrategy::None,
+                    WrappingStrategy::AtCol(col),
+                    WrappingStrategy::SpaceBeforeCol(col),
+                    WrappingStrategy::AtColWithHyphen(col),
+                    WrappingStrategy::Cutoff(col),
+                    WrappingStrategy::Ellipsis(col),
+                ][rng.usize(..6)],
+                positioning: [
+                    Positioning::LeftTop,
+                    Positioning::CenterTop,
+                    Positioning::RightTop,
+                    Positioning::LeftCenter,
+                    Positioning::Center,
+                    Positioning::RightCenter,
+                    Positioning::LeftBottom,
+                    Positioning::CenterBottom,
+                    Positioning::RightBottom,
+                ][rng.usize(..9)],
+            }
+        }
+        8 => Op::IndexedImage {
+            pos: gen_coord(rng),
+            width: rng.u8(1..=64),
+            height: rng.u8(1..=64),
+            colors: rng.u8(1..=8),
+        },
+        _ => Op::Image {
+            pos: gen_coord(rng),
+            width: rng.usize(1..=64),
+            height: rng.usize(1..=64),
+        },
+    }
+}
+
+const IMAGE_PALETTE: [Color; 8] = [TRANSPARENT, WHITE, RED, GREEN, BLUE, YELLOW, MAGENTA, CYAN];
+
+fn indexed_image(width: u8, height: u8, colors: u8) -> IndexedImage {
+    IndexedImage::new(
+        width,
+        height,
+        IMAGE_PALETTE[..colors as usize].to_vec(),
+        (0..width as usize * height as usize)
+            .map(|i| (i % colors as usize) as u8)
+            .collect(),
+    )
+    .expect("invalid fuzz image")
+}
+
+fn image(width: usize, height: usize) -> Image {
+    Image::new(
+        (0..width * height)
+            .map(|i| IMAGE_PALETTE[i % IMAGE_PALETTE.len()])
+            .collect(),
+        width,
+        height,
+    )
+    .expect("invalid fuzz image")
+}
+
+macro_rules! apply_transforms {
+    ($target: expr, $transforms: expr) => {{
+        let mut target = $target;
+        for transform in $transforms {
+            target = match transform {
+                Transform::Rotation(degrees) => target.with_rotation(*degrees),
+                Transform::RotationAround(degrees, point) => {
+                    target.with_rotation_around(*degrees, *point)
+                }
+                Transform::Scale(scale) => target.with_scale(*scale),
+                Transform::Move(xy) => target.with_move(*xy),
+                Transform::Translation(delta) => target.with_translation(*delta),
+            };
+        }
+        target
+    }};
+}
+
+fn draw_drawable<T>(
+    graphics: &mut Graphics,
+    drawable: Drawable<T>,
+    transforms: &[Transform],
+    offset: Option<Coord>,
+) where
+    T: Shape + Clone,
+    Drawable<T>: CreateDrawable<T> + Renderable<T>,
+{
+    let drawable = apply_transforms!(drawable, transforms);
+    match offset {
+        Some(offset) => graphics.draw_offset(offset, &drawable),
+        None => graphics.draw(&drawable),
+    }
+}
+
+impl Op {
+    fn draw(&self, graphics: &mut Graphics) {
+        match self {
+            Op::Drawable {
+                shape,
+                draw_type,
+                transforms,
+                offset,
+            } => {
+                let draw_type = *draw_type;
+                match shape.clone() {
+                    ShapeDef::Line(start, end) => draw_drawable(
+                        graphics,
+                        Drawable::from_obj(Line::new(start, end), draw_type),
+                        transforms,
+                        *offset,
+                    ),
+                    ShapeDef::Rect(start, end) => draw_drawable(
+                        graphics,
+                        Drawable::from_obj(Rect::new(start, end), draw_type),
+                        transforms,
+                        *offset,
+                    ),
+                    ShapeDef::Circle(center, radius) => draw_drawable(
+                        graphics,
+                        Drawable::from_obj(Circle::new(center, radius), draw_type),
+                        transforms,
+                        *offset,
+                    ),
+                    ShapeDef::Ellipse(center, w, h) => draw_drawable(
+                        graphics,
+                        Drawable::from_obj(Ellipse::new(center, w, h), draw_type),
+                        transforms,
+                        *offset,
+                    ),
+                    ShapeDef::Triangle(p1, p2, p3) => draw_drawable(
+                        graphics,
+                        Drawable::from_obj(Triangle::new(p1, p2, p3), draw_type),
+                        transforms,
+                        *offset,
+                    ),
+                    ShapeDef::Polygon(points) => draw_drawable(
+                        graphics,
+                        Drawable::from_obj(Polygon::new(&points), draw_type),
+                        transforms,
+                        *offset,
+                    ),
+                }
+            }
+            Op::Collection { shapes, transforms } => {
+                let mut collection = ShapeCollection::default();
+                for (shape, draw_type) in shapes.clone() {
+                    match shape {
+                        ShapeDef::Line(start, end) => InsertShape::insert_above(
+                            &mut collection,
+                            Line::new(start, end),
+                            draw_type,
+                        ),
+                        ShapeDef::Rect(start, end) => InsertShape::insert_above(
+                            &mut collection,
+                            Rect::new(start, end),
+                            draw_type,
+                        ),
+                        ShapeDef::Circle(center, radius) => InsertShape::insert_above(
+                            &mut collection,
+                            Circle::new(center, radius),
+                            draw_type,
+                        ),
+                        ShapeDef::Ellipse(center, w, h) => InsertShape::insert_above(
+                            &mut collection,
+                            Ellipse::new(center, w, h),
+                            draw_type,
+                        ),
+                        ShapeDef::Triangle(p1, p2, p3) => InsertShape::insert_above(
+                            &mut collection,
+                            Triangle::new(p1, p2, p3),
+                            draw_type,
+                        ),
+                        ShapeDef::Polygon(points) => InsertShape::insert_above(
+                            &mut collection,
+                            Polygon::new(&points),
+                            draw_type,
+                        ),
+                    }
+                }
+                graphics.draw(&apply_transforms!(collection, transforms));
+            }
+            Op::Text {
+                text,
+                pos,
+                color,
+                font,
+                wrapping,
+                positioning,
+            } => graphics.draw_text(
+                text,
+                TextPos::px(*pos),
+                (*color, *font, *wrapping, *positioning),
+            ),
+            Op::IndexedImage {
+                pos,
+                width,
+                height,
+                colors,
+            } => graphics.draw_indexed_image(*pos, &indexed_image(*width, *height, *colors)),
+            Op::Image { pos, width, height } => graphics.draw_image(*pos, &image(*width, *height)),
+        }
+    }
+
+    /// Versions of this op with one thing removed or made smaller
+    fn simplified(&self) -> Vec<Op> {
+        let mut output = vec![];
+        match self {
+            Op::Drawable {
+                shape,
+                draw_type,
+                transforms,
+                offset,
+            } => {
+                for i in 0..transforms.len() {
+                    let mut transforms = transforms.clone();
+                    transforms.remove(i);
+                    output.push(Op::Drawable {
+                        shape: shape.clone(),
+                        draw_type: *draw_type,
+                        transforms,
+                        offset: *offset,
+                    });
+                }
+                if offset.is_some() {
+                    output.push(Op::Drawable {
+                        shape: shape.clone(),
+                        draw_type: *draw_type,
+                        transforms: transforms.clone(),
+                        offset: None,
+                    });
+                }
+            }
+            Op::Collection { shapes, transforms } => {
+                for i in 0..transforms.len() {
+                    let mut transforms = transforms.clone();
+                    transforms.remove(i);
+                    output.push(Op::Collection {
+                        shapes: shapes.clone(),
+                        transforms,
+                    });
+                }
+                for i in 0..shapes.len() {
+                    let mut shapes = shapes.clone();
+                    shapes.remove(i);
+                    output.push(Op::Collection {
+                        shapes,
+                        transforms: transforms.clone(),
+                    });
+                }
+            }
+            Op::Text {
+                text,
+                pos,
+                color,
+                font,
+                wrapping,
+                positioning,
+            } => {
+                let text_op =
+                    |text: &str, wrapping: WrappingStrategy, positioning: Positioning| Op::Text {
+                        text: text.to_string(),
+                        pos: *pos,
+                        color: *color,
+                        font: *font,
+                        wrapping,
+                        positioning,
+                    };
+                let chars: Vec<char> = text.chars().collect();
+                if !chars.is_empty() {
+                    let half = chars.len() / 2;
+                    output.push(text_op(
+                        &chars[..half].iter().collect::<String>(),
+                        *wrapping,
+                        *positioning,
+                    ));
+                    output.push(text_op(
+                        &chars[half..].iter().collect::<String>(),
+                        *wrapping,
+                        *positioning,
+                    ));
+                    output.push(text_op(
+                        &chars[1..].iter().collect::<String>(),
+                        *wrapping,
+                        *positioning,
+                    ));
+                }
+                if *wrapping != WrappingStrategy::None {
+                    output.push(text_op(text, WrappingStrategy::None, *positioning));
+                }
+                if *positioning != Positioning::LeftTop {
+                    output.push(text_op(text, *wrapping, Positioning::LeftTop));
+                }
+            }
+            Op::IndexedImage {
+                pos,
+                width,
+                height,
+                colors,
+            } => {
+                if *width > 1 || *height > 1 {
+                    output.push(Op::IndexedImage {
+                        pos: *pos,
+                        width: (*width / 2).max(1),
+                        height: (*height / 2).max(1),
+                        colors: *colors,
+                    });
+                }
+            }
+            Op::Image { pos, width, height } => {
+                if *width > 1 || *height > 1 {
+                    output.push(Op::Image {
+                        pos: *pos,
+                        width: (*width / 2).max(1),
+                        height: (*height / 2).max(1),
+                    });
+                }
+            }
+        }
+        output
+    }
+
+    fn to_rust(&self) -> String {
+        match self {
+            Op::Drawable {
+                shape,
+                draw_type,
+                transforms,
+                offset,
+            } => {
+                let drawable = format!(
+                    "Drawable::from_obj({}, {}){}",
+                    shape.to_rust(),
+                    draw_type_rust(draw_type),
+                    transforms_rust(transforms)
+                );
+                match offset {
+                    Some(offset) => format!("graphics.draw_offset({}, &{drawable});", coord_rust(offset)),
+                    None => format!("graphics.draw(&{drawable});"),
+                }
+            }
+            Op::Collection { shapes, transforms } => {
+                let mut output = String::from("{\n        let mut collection = ShapeCollection::default();\n");
+                for (shape, draw_type) in shapes {
+                    let _ = writeln!(
+                        output,
+                        "        InsertShape::insert_above(&mut collection, {}, {});",
+                        shape.to_rust(),
+                        draw_type_rust(draw_type)
+                    );
+                }
+                let _ = write!(
+                    output,
+                    "        graphics.draw(&collection{});\n    }}",
+                    transforms_rust(transforms)
+                );
+                output
+            }
+            Op::Text {
+                text,
+                pos,
+                color,
+                font,
+                wrapping,
+                positioning,
+            } => format!(
+                "graphics.draw_text({text:?}, TextPos::px({}), ({}, PixelFont::{font:?}, WrappingStrategy::{wrapping:?}, Positioning::{positioning:?}));",
+                coord_rust(pos),
+                color_rust(color)
+            ),
+            Op::IndexedImage {
+                pos,
+                width,
+                height,
+                colors,
+            } => format!(
+                "graphics.draw_indexed_image({}, &IndexedImage::new({width}, {height}, vec![{}], (0..{width}usize * {height}).map(|i| (i % {colors}) as u8).collect()).unwrap());",
+                coord_rust(pos),
+                IMAGE_PALETTE[..*colors as usize]
+                    .iter()
+                    .map(color_rust)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Op::Image { pos, width, height } => format!(
+                "graphics.draw_image({}, &Image::new((0..{width} * {height}).map(|i| [{}][i % {}]).collect(), {width}, {height}).unwrap());",
+                coord_rust(pos),
+                IMAGE_PALETTE
+                    .iter()
+                    .map(color_rust)
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                IMAGE_PALETTE.len()
+            ),
+        }
+    }
+}
+
+impl ShapeDef {
+    fn to_rust(&self) -> String {
+        match self {
+            ShapeDef::Line(start, end) => {
+                format!("Line::new({}, {})", coord_rust(start), coord_rust(end))
+            }
+            ShapeDef::Rect(start, end) => {
+                format!("Rect::new({}, {})", coord_rust(start), coord_rust(end))
+            }
+            ShapeDef::Circle(center, radius) => {
+                format!("Circle::new({}, {radius})", coord_rust(center))
+            }
+            ShapeDef::Ellipse(center, w, h) => {
+                format!("Ellipse::new({}, {w}, {h})", coord_rust(center))
+            }
+            ShapeDef::Triangle(p1, p2, p3) => format!(
+                "Triangle::new({}, {}, {})",
+                coord_rust(p1),
+                coord_rust(p2),
+                coord_rust(p3)
+            ),
+            ShapeDef::Polygon(points) if points.is_empty() => {
+                String::from("Polygon::new(&[] as &[Coord])")
+            }
+            ShapeDef::Polygon(points) => format!(
+                "Polygon::new(&[{}])",
+                points
+                    .iter()
+                    .map(coord_rust)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+fn coord_rust(coord: &Coord) -> String {
+    format!("Coord::new({}, {})", coord.x, coord.y)
+}
+
+fn color_rust(color: &Color) -> String {
+    format!(
+        "Color::new({}, {}, {}, {})",
+        color.r, color.g, color.b, color.a
+    )
+}
+
+fn draw_type_rust(draw_type: &DrawType) -> String {
+    match draw_type {
+        DrawType::Stroke(color) => format!("stroke({})", color_rust(color)),
+        DrawType::Fill(color) => format!("fill({})", color_rust(color)),
+    }
+}
+
+fn transforms_rust(transforms: &[Transform]) -> String {
+    transforms
+        .iter()
+        .map(|transform| match transform {
+            Transform::Rotation(degrees) => format!(".with_rotation({degrees})"),
+            Transform::RotationAround(degrees, point) => {
+                format!(".with_rotation_around({degrees}, {})", coord_rust(point))
+            }
+            Transform::Scale(scale) => format!(".with_scale({scale:?})"),
+            Transform::Move(xy) => format!(".with_move({})", coord_rust(xy)),
+            Transform::Translation(delta) => format!(".with_translation({})", coord_rust(delta)),
+        })
+        .collect()
+}
+
+fn to_test_fn(ops: &[Op], case_seed: u64) -> String {
+    let mut output = format!(
+        "fn test_fuzz_{case_seed}(graphics: &mut Graphics) {{\n    draw_title(graphics, \"Fuzz {case_seed}\");\n\n"
+    );
+    for op in ops {
+        let _ = writeln!(output, "    {}", op.to_rust());
+    }
+    output.push('}');
+    output
+}
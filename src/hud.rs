@@ -0,0 +1,125 @@
+use pixels_graphics_lib::prelude::*;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Frame time a 60Hz display allows, frames slower than this are highlighted
+const FRAME_BUDGET: f64 = 1.0 / 60.0;
+/// Number of frames shown in the graph, one pixel each
+const HISTORY: usize = 140;
+const GRAPH_HEIGHT: isize = 30;
+/// Frame time at the top of the graph, the budget line is halfway up
+const GRAPH_MAX: f64 = FRAME_BUDGET * 2.0;
+const PANEL: Coord = Coord::new(2, 15);
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// Time since the previous render, in seconds
+    time: f64,
+    /// Number of `update` calls since the previous render
+    updates: usize,
+}
+
+/// Toggleable overlay showing what the harness is doing with [Timing]
+///
+/// `update` is called zero or more times between renders, so frames are detected by
+/// [Timing::renders] changing
+#[derive(Debug)]
+pub struct TimingHud {
+    pub visible: bool,
+    frames: VecDeque<Frame>,
+    last_renders: usize,
+    last_frame_at: Option<Instant>,
+    updates_since_render: usize,
+    fps: usize,
+    lag: f64,
+    fixed_time_step: f64,
+    /// Time taken by [System::render] for the last frame, excluding the HUD
+    render_time: Duration,
+}
+
+impl TimingHud {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            frames: VecDeque::with_capacity(HISTORY),
+            last_renders: 0,
+            last_frame_at: None,
+            updates_since_render: 0,
+            fps: 0,
+            lag: 0.0,
+            fixed_time_step: 0.0,
+            render_time: Duration::ZERO,
+        }
+    }
+
+    pub fn update(&mut self, timing: &Timing) {
+        if timing.renders != self.last_renders {
+            if let Some(last) = self.last_frame_at {
+                if self.frames.len() == HISTORY {
+                    self.frames.pop_front();
+                }
+                self.frames.push_back(Frame {
+                    time: timing.now.duration_since(last).as_secs_f64(),
+                    updates: self.updates_since_render,
+                });
+            }
+            self.last_frame_at = Some(timing.now);
+            self.last_renders = timing.renders;
+            self.updates_since_render = 0;
+        }
+        self.updates_since_render += 1;
+        self.fps = timing.stats.fps;
+        self.lag = timing.accumulated_time;
+        self.fixed_time_step = timing.fixed_time_step;
+    }
+
+    pub fn set_render_time(&mut self, render_time: Duration) {
+        self.render_time = render_time;
+    }
+
+    pub fn render(&self, graphics: &mut Graphics) {
+        let last = self.frames.back().copied().unwrap_or(Frame {
+            time: 0.0,
+            updates: 0,
+        });
+        let missed = self
+            .frames
+            .iter()
+            .filter(|frame| frame.time > FRAME_BUDGET)
+            .count();
+        let text_color = if last.time > FRAME_BUDGET { RED } else { WHITE };
+
+        graphics.draw_rect(
+            Rect::new_with_size(PANEL, HISTORY + 4, 36 + GRAPH_HEIGHT as usize),
+            fill(Color::new(0, 0, 0, 200)),
+        );
+        graphics.draw_text(
+            &format!(
+                "FPS: {}\nFrame: {:.1}ms  Render: {:.1}ms\nUpdates/render: {}\nLag: {:.2}ms  Step: {:.2}ms\nMissed budget: {missed}/{}",
+                self.fps,
+                last.time * 1000.0,
+                self.render_time.as_secs_f64() * 1000.0,
+                last.updates,
+                self.lag * 1000.0,
+                self.fixed_time_step * 1000.0,
+                self.frames.len()
+            ),
+            TextPos::px(PANEL + (2, 2)),
+            (text_color, PixelFont::Standard4x5),
+        );
+
+        let bottom = PANEL.y + 34 + GRAPH_HEIGHT;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let height = ((frame.time / GRAPH_MAX) * GRAPH_HEIGHT as f64).round() as isize;
+            let color = if frame.time > FRAME_BUDGET { RED } else { GREEN };
+            let x = PANEL.x + 2 + i as isize;
+            graphics.draw_line((x, bottom), (x, bottom - height.clamp(0, GRAPH_HEIGHT)), color);
+        }
+        let budget_y = bottom - GRAPH_HEIGHT / 2;
+        graphics.draw_line(
+            (PANEL.x + 2, budget_y),
+            (PANEL.x + 1 + HISTORY as isize, budget_y),
+            YELLOW,
+        );
+    }
+}
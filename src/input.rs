@@ -0,0 +1,208 @@
+//! Recording and replaying mouse moves and key releases
+//!
+//! Times are counted in updates (converted to seconds at [UPDATES_PER_SECOND]) rather than wall
+//! clock time, as that's what drives the animations, so replaying with [Example::set_time]
+//! reproduces the same frames.
+
+use crate::control::status;
+use crate::{capture, Example, SCREEN_HEIGHT, SCREEN_WIDTH, UPDATES_PER_SECOND};
+use anyhow::{bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const RECORDING_DIR: &str = "recordings";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    MouseMove(isize, isize),
+    /// Key name as printed by [KeyCode]'s `Debug`, i.e. `ArrowRight`
+    KeyUp(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    /// Seconds since recording started
+    pub time: f64,
+    pub event: InputEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub start_test: usize,
+    pub events: Vec<TimedEvent>,
+    /// Seconds from starting to stopping the recording
+    pub end: f64,
+    /// [capture::frame_hash] of the frame shown when recording stopped
+    pub frame_hash: Option<u64>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        ron::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    ALL_KEYS
+        .iter()
+        .find(|key| key_name(**key) == name)
+        .copied()
+}
+
+fn seconds(updates: usize) -> f64 {
+    updates as f64 / UPDATES_PER_SECOND as f64
+}
+
+/// Collects input while the window is open, started and stopped with R
+#[derive(Debug)]
+pub struct InputRecorder {
+    recording: Recording,
+    updates: usize,
+    /// Set when R is released, the recording is saved on the next render so the frame can be hashed
+    pub stopping: bool,
+}
+
+impl InputRecorder {
+    pub fn new(start_test: usize) -> Self {
+        Self {
+            recording: Recording {
+                start_test,
+                events: vec![],
+                end: 0.0,
+                frame_hash: None,
+            },
+            updates: 0,
+            stopping: false,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.updates += 1;
+    }
+
+    pub fn add(&mut self, event: InputEvent) {
+        self.recording.events.push(TimedEvent {
+            time: seconds(self.updates),
+            event,
+        });
+    }
+
+    /// Write the recording to [RECORDING_DIR], returning the path
+    pub fn save(mut self, frame: &Image) -> Result<PathBuf> {
+        self.recording.end = seconds(self.updates);
+        self.recording.frame_hash = Some(capture::frame_hash(frame));
+        fs::create_dir_all(RECORDING_DIR)?;
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = Path::new(RECORDING_DIR).join(format!("input_{secs}.ron"));
+        let text =
+            ron::ser::to_string_pretty(&self.recording, ron::ser::PrettyConfig::default())?;
+        fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Feeds a recording back into the window
+#[derive(Debug)]
+pub struct Replay {
+    recording: Recording,
+    next: usize,
+    updates: usize,
+    /// Set once every event has been played and the time has reached the end of the recording,
+    /// the next frame is then checked against the recorded hash
+    pub finished: bool,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            updates: 0,
+            finished: false,
+        }
+    }
+
+    pub fn end(&self) -> f64 {
+        self.recording.end
+    }
+
+    pub fn frame_hash(&self) -> Option<u64> {
+        self.recording.frame_hash
+    }
+
+    /// Advance by one update, returning the events that are now due
+    pub fn update(&mut self) -> Vec<InputEvent> {
+        self.updates += 1;
+        let now = seconds(self.updates);
+        let mut due = vec![];
+        while let Some(event) = self.recording.events.get(self.next) {
+            if event.time > now {
+                break;
+            }
+            due.push(event.event.clone());
+            self.next += 1;
+        }
+        due
+    }
+
+    pub fn reached_end(&self) -> bool {
+        self.next >= self.recording.events.len() && seconds(self.updates) >= self.recording.end
+    }
+}
+
+/// Compare a frame against the recording, printing the result
+pub fn check_frame(expected: Option<u64>, frame: &Image) -> bool {
+    let actual = capture::frame_hash(frame);
+    match expected {
+        Some(expected) if expected == actual => {
+            status!("[PASS] final frame matches recording ({actual:016x})");
+            true
+        }
+        Some(expected) => {
+            status!("[FAIL] final frame {actual:016x}, recording has {expected:016x}");
+            false
+        }
+        None => {
+            status!("Final frame {actual:016x}, recording has no hash");
+            true
+        }
+    }
+}
+
+/// Replay without a window, returns an error if the final frame doesn't match the recording
+pub fn replay_headless(recording: Recording, output: Option<&Path>) -> Result<()> {
+    let mut example = Example::new();
+    example.current_test = recording.start_test;
+    for timed in &recording.events {
+        example.apply_input(&timed.event);
+    }
+    //the overlays are drawn after the frame is hashed in the window
+    example.hud.visible = false;
+    example.grid = false;
+    example.loupe = false;
+    example.help = false;
+    example.set_time(recording.end);
+
+    let mut buffer = Graphics::create_buffer_u8(SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize);
+    let mut graphics =
+        Graphics::new_u8_rgba(&mut buffer, SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize)?;
+    example.render(&mut graphics);
+    let frame = graphics.copy_to_image();
+    if let Some(path) = output {
+        capture::save_png(path, &frame)?;
+        println!("Saved {}", path.display());
+    }
+    if !check_frame(recording.frame_hash, &frame) {
+        bail!("replay didn't match recording");
+    }
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! JUnit XML output for CI dashboards
+//!
+//! Written by `check --junit FILE`, with a `snapshots` suite containing one case per visual
+//! test, an `invariants` suite with one case per [checks::SUITES] entry and an `assets` suite
+//! with one case per file loaded by [assets::check_all].
+
+use crate::assets::AssetResult;
+use crate::checks;
+use crate::snapshot::{Status, TestResult};
+use anyhow::{Context, Result};
+use fastrand::Rng;
+use std::fmt::Write;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::time::Instant;
+
+/// Invariant failures listed in a case's body
+const MAX_LISTED: usize = 25;
+
+pub struct Suite {
+    pub name: &'static str,
+    pub cases: Vec<Case>,
+}
+
+pub struct Case {
+    pub name: String,
+    /// Seconds
+    pub time: f64,
+    pub outcome: Outcome,
+}
+
+pub enum Outcome {
+    Passed,
+    /// The test ran and found a problem
+    Failure {
+        kind: &'static str,
+        message: String,
+        details: String,
+    },
+    /// The test couldn't run
+    Error {
+        kind: &'static str,
+        message: String,
+        details: String,
+    },
+}
+
+impl Suite {
+    pub fn failures(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Failure { .. }))
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Error { .. }))
+    }
+
+    fn count(&self, filter: fn(&Outcome) -> bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| filter(&case.outcome))
+            .count()
+    }
+
+    fn time(&self) -> f64 {
+        self.cases.iter().map(|case| case.time).sum()
+    }
+}
+
+pub fn snapshot_suite(results: &[TestResult]) -> Suite {
+    let cases = results
+        .iter()
+        .map(|result| {
+            let diff = result
+                .diff
+                .as_ref()
+                .map(|path| format!("diff: {}", path.display()))
+                .unwrap_or_default();
+            let outcome = match &result.status {
+                Status::Pass => Outcome::Passed,
+                Status::Mismatch(_) | Status::SizeMismatch(..) => Outcome::Failure {
+                    kind: "snapshot_mismatch",
+                    message: result.status.describe(),
+                    details: diff,
+                },
+                Status::NoBaseline => Outcome::Failure {
+                    kind: "missing_baseline",
+                    message: result.status.describe(),
+                    details: String::new(),
+                },
+                Status::Panicked(message) => Outcome::Error {
+                    kind: "panic",
+                    message: message.clone(),
+                    details: String::new(),
+                },
+                Status::Error(message) => Outcome::Error {
+                    kind: "io",
+                    message: message.clone(),
+                    details: String::new(),
+                },
+            };
+            Case {
+                name: result.title.clone(),
+                time: result.render_time.as_secs_f64(),
+                outcome,
+            }
+        })
+        .collect();
+    Suite {
+        name: "snapshots",
+        cases,
+    }
+}
+
+/// Run every invariant suite, printing a line per suite
+pub fn invariant_suite(seed: u64) -> Suite {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut cases = vec![];
+    for suite in checks::SUITES {
+        let mut rng = Rng::with_seed(seed);
+        let start = Instant::now();
+        let result = (suite.run)(&mut rng);
+        let time = start.elapsed().as_secs_f64();
+        let outcome = if result.failures.is_empty() {
+            println!("[PASS] {}", suite.name);
+            Outcome::Passed
+        } else {
+            let message = format!(
+                "{} of {} cases failed (seed {seed})",
+                result.failures.len(),
+                result.cases
+            );
+            println!("[FAIL] {}: {message}", suite.name);
+            let mut details = String::new();
+            for failure in result.failures.iter().take(MAX_LISTED) {
+                details.push_str(&format!("{}\n  {}\n", failure.input, failure.message));
+            }
+            if result.failures.len() > MAX_LISTED {
+                details.push_str(&format!(
+                    "...and {} more\n",
+                    result.failures.len() - MAX_LISTED
+                ));
+            }
+            Outcome::Failure {
+                kind: "invariant_violation",
+                message,
+                details,
+            }
+        };
+        cases.push(Case {
+            name: suite.name.to_string(),
+            time,
+            outcome,
+        });
+    }
+
+    panic::set_hook(default_hook);
+    Suite {
+        name: "invariants",
+        cases,
+    }
+}
+
+/// Print a line per asset
+pub fn asset_suite(results: Vec<AssetResult>) -> Suite {
+    let cases = results
+        .into_iter()
+        .map(|result| {
+            let outcome = match result.error {
+                None => {
+                    println!("[PASS] {}", result.name);
+                    Outcome::Passed
+                }
+                Some(message) => {
+                    println!("[FAIL] {}: {message}", result.name);
+                    Outcome::Error {
+                        kind: "asset_error",
+                        message,
+                        details: String::new(),
+                    }
+                }
+            };
+            Case {
+                name: result.name,
+                time: 0.0,
+                outcome,
+            }
+        })
+        .collect();
+    Suite {
+        name: "assets",
+        cases,
+    }
+}
+
+pub fn write(path: &Path, suites: &[Suite]) -> Result<()> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let tests: usize = suites.iter().map(|suite| suite.cases.len()).sum();
+    let failures: usize = suites.iter().map(Suite::failures).sum();
+    let errors: usize = suites.iter().map(Suite::errors).sum();
+    let time: f64 = suites.iter().map(Suite::time).sum();
+    writeln!(
+        xml,
+        "<testsuites name=\"graphics_tester\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">"
+    )?;
+    for suite in suites {
+        writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            suite.name,
+            suite.cases.len(),
+            suite.failures(),
+            suite.errors(),
+            suite.time()
+        )?;
+        for case in &suite.cases {
+            write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"graphics_tester.{}\" time=\"{:.6}\"",
+                escape(&case.name),
+                suite.name,
+                case.time
+            )?;
+            let (tag, kind, message, details) = match &case.outcome {
+                Outcome::Passed => {
+                    writeln!(xml, "/>")?;
+                    continue;
+                }
+                Outcome::Failure {
+                    kind,
+                    message,
+                    details,
+                } => ("failure", kind, message, details),
+                Outcome::Error {
+                    kind,
+                    message,
+                    details,
+                } => ("error", kind, message, details),
+            };
+            writeln!(
+                xml,
+                ">\n      <{tag} type=\"{kind}\" message=\"{}\">{}</{tag}>\n    </testcase>",
+                escape(message),
+                escape(details)
+            )?;
+        }
+        writeln!(xml, "  </testsuite>")?;
+    }
+    writeln!(xml, "</testsuites>")?;
+    fs::write(path, xml).with_context(|| format!("writing {}", path.display()))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
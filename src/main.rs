@@ -4,13 +4,48 @@ use pixels_graphics_lib::buffer_graphics_lib::CustomLetter;
 use pixels_graphics_lib::prelude::palette::simplify_palette;
 use pixels_graphics_lib::prelude::*;
 use pixels_graphics_lib::prelude::font::standard_4x5;
-use pixels_graphics_lib::prelude::KeyCode::KeyC;
-
+use crate::bindings::{Action, Bindings};
+use crate::category::{Category, Filter};
+use crate::compare::{Compare, Side};
+use crate::control::{status, Command, Control, Request};
+use crate::hud::TimingHud;
+use crate::input::{InputEvent, InputRecorder, Recording, Replay};
+use crate::overview::Overview;
+use crate::scene::Scene;
+use std::cell::RefCell;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+mod assets;
+mod bench;
+mod bindings;
+mod capture;
+mod category;
+mod checks;
+mod compare;
+mod cli;
+mod control;
+mod fuzz;
+mod hud;
+mod input;
+mod junit;
+mod overlay;
+mod overview;
+mod prefs;
+mod record;
+mod report;
+mod scene;
+mod snapshot;
+
+#[derive(Clone)]
 struct Animation {
     pub value: f32,
     pub value_change: f32,
     pub next_update: f32,
     pub update_rate: f32,
+    /// `value` and `next_update` when created
+    start: (f32, f32),
 }
 
 impl Animation {
@@ -25,21 +60,129 @@ impl Animation {
             self.next_update = self.update_rate;
         }
     }
+    fn reset(&mut self) {
+        (self.value, self.next_update) = self.start;
+    }
+
     pub fn new(value: f32, value_change: f32, next_update: f32, update_rate: f32) -> Self {
         Self {
             value,
             value_change,
             next_update,
             update_rate,
+            start: (value, next_update),
+        }
+    }
+}
+
+struct PaletteExplorer {
+    sources: Vec<(&'static str, IndexedImage)>,
+    source: usize,
+    threshold: f32,
+    threshold_change: f32,
+}
+
+impl PaletteExplorer {
+    /// Max value of [Color::diff]
+    const MAX_THRESHOLD: f32 = 1020.0;
+    /// Threshold change per second while a key is held
+    const SPEED: f32 = 60.0;
+
+    fn threshold_int(&self) -> usize {
+        self.threshold as usize
+    }
+
+    fn update(&mut self, delta: f32) {
+        self.threshold =
+            (self.threshold + self.threshold_change * delta).clamp(0.0, Self::MAX_THRESHOLD);
+    }
+
+    fn adjust(&mut self, amount: f32) {
+        self.threshold = (self.threshold + amount).clamp(0.0, Self::MAX_THRESHOLD);
+    }
+
+    fn next_source(&mut self) {
+        self.source = (self.source + 1) % self.sources.len();
+    }
+
+    pub fn new(sources: Vec<(&'static str, IndexedImage)>) -> Self {
+        Self {
+            sources,
+            source: 0,
+            threshold: 20.0,
+            threshold_change: 0.0,
         }
     }
 }
 
+/// Settings and timings for the stress pages, tests [Stress::FIRST] onwards
+struct Stress {
+    counts: [usize; 5],
+    /// Smoothed time between frames, in seconds
+    frame_time: f64,
+    /// Time taken to draw the last stress page, excluding the overlay
+    render_time: Duration,
+}
+
+impl Stress {
+    const FIRST: usize = 65;
+    const MAX_COUNTS: [usize; 5] = [1_000_000, 500_000, 200_000, 100_000, 7];
+    const SEED: u64 = 65;
+
+    fn is_stress(test: usize) -> bool {
+        (Self::FIRST..Self::FIRST + Self::MAX_COUNTS.len()).contains(&test)
+    }
+
+    fn count_mut(&mut self, test: usize) -> Option<&mut usize> {
+        self.counts.get_mut(test.checked_sub(Self::FIRST)?)
+    }
+
+    fn increase(&mut self, test: usize) {
+        if let Some(count) = self.count_mut(test) {
+            *count = (*count * 2).min(Self::MAX_COUNTS[test - Self::FIRST]);
+        }
+    }
+
+    fn decrease(&mut self, test: usize) {
+        if let Some(count) = self.count_mut(test) {
+            *count = (*count / 2).max(1);
+        }
+    }
+
+    fn update(&mut self, delta: f64) {
+        self.frame_time += (delta - self.frame_time) * 0.1;
+    }
+
+    pub fn new() -> Self {
+        let font = PixelFont::Standard4x4;
+        let full_screen_chars = (SCREEN_WIDTH as usize / font.char_width())
+            * ((SCREEN_HEIGHT as usize - 14) / font.line_height());
+        Self {
+            counts: [10_000, 5_000, full_screen_chars, 300, 5],
+            frame_time: 0.0,
+            render_time: Duration::ZERO,
+        }
+    }
+}
+
+/// A test rendered offscreen by [Example::render_tests]
+struct Rendered {
+    test: usize,
+    /// Text passed to [draw_title], or `N) Untitled` if there wasn't any
+    title: String,
+    /// Time taken to render, excluding copying the frame
+    time: Duration,
+    /// The frame, or the panic message
+    image: Result<Image, String>,
+}
+
 struct Example {
     current_test: usize,
     fast: Animation,
     slow: Animation,
     should_quit: bool,
+    /// Set by Escape, the window placement is saved on the next update then [Self::should_quit] is set
+    quit_requested: bool,
     ici_static: IndexedImage,
     ici_slow: AnimatedIndexedImage,
     ici_fast: AnimatedIndexedImage,
@@ -47,76 +190,525 @@ struct Example {
     mouse_xy: Coord,
     tilemap: Tilemap<IndexedImage>,
     tilemap2: Tilemap<IndexedImage>,
-    map_center: MapPosition
+    map_center: MapPosition,
+    ici_tileset: IciTileset,
+    palette_explorer: PaletteExplorer,
+    stress: Stress,
+    hud: TimingHud,
+    screenshot_requested: bool,
+    input_recorder: Option<InputRecorder>,
+    replay: Option<Replay>,
+    /// Shown after the built-in tests, from [TEST_COUNT]
+    scenes: Vec<Scene>,
+    grid: bool,
+    loupe: bool,
+    /// Only loaded when running the window, saved when it's closed
+    prefs: Option<AppPrefs<prefs::Settings>>,
+    /// Set by the reset key so the current window placement isn't saved on close
+    window_reset: bool,
+    bindings: Bindings,
+    help: bool,
+    overview: Overview,
+    compare: Compare,
+    control: Option<Control>,
+    /// Set by the `set-time` control command so the animations hold still
+    time_frozen: bool,
+    /// Saved with the next frame by the `screenshot` control command
+    control_screenshot: Option<(PathBuf, Request)>,
 }
 
-fn main() -> Result<()> {
-    let ici_tileset: IciTileset = ron::from_str(include_str!("../assets/test.ici_tileset")).unwrap();
-    let tilemap_file: TilemapFile = ron::from_str(include_str!("../assets/test.tilemap")).unwrap();
-    let tileset = ici_tileset.into_tileset().unwrap();
-    let tilemap: Tilemap<IndexedImage> = tilemap_file.into_tilemap(&tileset, (200,200)).unwrap();
-    let tilemap_file: TilemapFile = ron::from_str(include_str!("../assets/test2.tilemap")).unwrap();
-    let tilemap2: Tilemap<IndexedImage> = tilemap_file.into_tilemap(&tileset, (200,200)).unwrap();
-    let (ici_static, _) =
-        IndexedImage::from_file_contents(include_bytes!("../assets/test.ici")).unwrap();
-    let (ici_slow, _) =
-        AnimatedIndexedImage::from_file_contents(include_bytes!("../assets/slow.ica")).unwrap();
-    let (ici_fast, _) =
-        AnimatedIndexedImage::from_file_contents(include_bytes!("../assets/fast.ica")).unwrap();
-    let (image, _) =
-        IndexedImage::from_file_contents(include_bytes!("../assets/image.ici")).unwrap();
-    let system = Box::new(Example {
-        map_center: MapPosition::new(4,4),
-        tilemap,
-        tilemap2,
-        image,
-        should_quit: false,
-        ici_static,
-        ici_slow,
-        current_test: 0,
-        fast: Animation::new(0.0, 1.0, 0.0, 0.001),
-        slow: Animation::new(0.0, 0.1, 0.0, 0.001),
-        ici_fast,
-        mouse_xy: Default::default(),
-    });
-    run(
-        SCREEN_WIDTH as usize,
-        SCREEN_HEIGHT as usize,
-        "Testing",
-        system,
-        Options::default(),
-    )?;
-    Ok(())
-}
+impl Example {
+    fn new() -> Self {
+        let ici_tileset: IciTileset = ron::from_str(include_str!("../assets/test.ici_tileset")).unwrap();
+        //into_tileset consumes it and IciTileset isn't Clone, so copy it for the browser page
+        let browser_tileset = IciTileset::new(ici_tileset.name.clone(), ici_tileset.tile_size(), ici_tileset.palette().clone(), ici_tileset.tiles().clone());
+        let tilemap_file: TilemapFile = ron::from_str(include_str!("../assets/test.tilemap")).unwrap();
+        let tileset = ici_tileset.into_tileset().unwrap();
+        let tilemap: Tilemap<IndexedImage> = tilemap_file.into_tilemap(&tileset, (200,200)).unwrap();
+        let tilemap_file: TilemapFile = ron::from_str(include_str!("../assets/test2.tilemap")).unwrap();
+        let tilemap2: Tilemap<IndexedImage> = tilemap_file.into_tilemap(&tileset, (200,200)).unwrap();
+        let (ici_static, _) =
+            IndexedImage::from_file_contents(include_bytes!("../assets/test.ici")).unwrap();
+        let (ici_slow, _) =
+            AnimatedIndexedImage::from_file_contents(include_bytes!("../assets/slow.ica")).unwrap();
+        let (ici_fast, _) =
+            AnimatedIndexedImage::from_file_contents(include_bytes!("../assets/fast.ica")).unwrap();
+        let (image, _) =
+            IndexedImage::from_file_contents(include_bytes!("../assets/image.ici")).unwrap();
+        let mut explorer_sources = vec![
+            ("image.ici", image.clone()),
+            ("test.ici", ici_static.clone()),
+            ("slow.ica", ici_slow.get_frame(0)),
+            ("fast.ica", ici_fast.get_frame(0)),
+        ];
+        if let Ok((img, _)) = IndexedImage::from_file_contents(include_bytes!("../assets/static.ici")) {
+            explorer_sources.push(("static.ici", img));
+        }
+        if let Some(img) = tileset_strip(&browser_tileset) {
+            explorer_sources.push(("test.ici_tileset", img));
+        }
+        let bindings = Bindings::load(Path::new(bindings::BINDINGS_FILE)).unwrap_or_else(|e| {
+            eprintln!("Using default key bindings: {e:#}");
+            Bindings::default()
+        });
+        let (scenes, scene_errors) = scene::load_all(Path::new(scene::SCENE_DIR));
+        for error in scene_errors {
+            eprintln!("Skipping scene: {error}");
+        }
+        Example {
+            map_center: MapPosition::new(4,4),
+            ici_tileset: browser_tileset,
+            palette_explorer: PaletteExplorer::new(explorer_sources),
+            stress: Stress::new(),
+            hud: TimingHud::new(),
+            screenshot_requested: false,
+            input_recorder: None,
+            replay: None,
+            scenes,
+            grid: false,
+            loupe: false,
+            prefs: None,
+            window_reset: false,
+            bindings,
+            help: false,
+            overview: Overview::default(),
+            compare: Compare::default(),
+            control: None,
+            time_frozen: false,
+            control_screenshot: None,
+            tilemap,
+            tilemap2,
+            image,
+            should_quit: false,
+            quit_requested: false,
+            ici_static,
+            ici_slow,
+            current_test: 0,
+            fast: Animation::new(0.0, 1.0, 0.0, 0.001),
+            slow: Animation::new(0.0, 0.1, 0.0, 0.001),
+            ici_fast,
+            mouse_xy: Default::default(),
+        }
+    }
 
-const KEYS: [KeyCode; 8] = [
-    KeyCode::ArrowLeft,
-    KeyCode::ArrowRight,
-    KeyCode::Space,
-    KeyCode::Escape,
-    KeyCode::KeyW,
-    KeyCode::KeyA,
-    KeyCode::KeyS,
-    KeyCode::KeyD,
-];
+    /// Built-in tests plus loaded scenes
+    fn test_count(&self) -> usize {
+        TEST_COUNT + self.scenes.len()
+    }
 
-impl System for Example {
-    fn keys_used(&self) -> &[KeyCode] {
-        &KEYS
+    /// Load the saved settings and restore the last test and overlays
+    fn restore_prefs(&mut self) {
+        self.prefs = prefs::app_prefs();
+        if let Some(prefs) = &self.prefs {
+            let settings = &prefs.data;
+            self.current_test = settings.current_test.min(self.test_count() - 1);
+            self.grid = settings.grid;
+            self.loupe = settings.loupe;
+            self.hud.visible = settings.hud;
+        }
     }
 
-    fn update(&mut self, timing: &Timing, _: &Window) {
-        self.fast.update(timing.delta as f32);
-        self.slow.update(timing.delta as f32);
-        self.ici_slow.update(timing.fixed_time_step);
-        self.ici_fast.update(timing.fixed_time_step);
+    fn save_prefs(&mut self) {
+        if let Some(prefs) = &mut self.prefs {
+            prefs.data = prefs::Settings {
+                current_test: self.current_test,
+                grid: self.grid,
+                loupe: self.loupe,
+                hud: self.hud.visible,
+            };
+            prefs.save();
+        }
     }
 
-    fn on_mouse_move(&mut self, mouse: &MouseData) {
-        self.mouse_xy = mouse.xy;
+    /// Go back to test 0 with no overlays and forget the window placement
+    fn reset_prefs(&mut self) {
+        self.current_test = 0;
+        self.grid = false;
+        self.loupe = false;
+        self.hud.visible = false;
+        if self.prefs.is_some() {
+            self.save_prefs();
+            prefs::delete_window_prefs();
+            self.window_reset = true;
+            status!("Saved state reset");
+        }
     }
 
-    fn render(&mut self, graphics: &mut Graphics) {
+    fn perform(&mut self, action: Action) {
+        match action {
+            Action::PreviousTest => self.current_test = self.current_test.saturating_sub(1),
+            Action::NextTest => self.current_test += 1,
+            Action::PreviousCategory => self.step_category(false),
+            Action::NextCategory => self.step_category(true),
+            Action::JumpToTilemap => self.current_test = 61,
+            Action::Increase => {
+                self.palette_explorer.threshold_change = 0.0;
+                self.stress.increase(self.current_test);
+            }
+            Action::Decrease => {
+                self.palette_explorer.threshold_change = 0.0;
+                self.stress.decrease(self.current_test);
+            }
+            Action::NextPaletteSource => {
+                if self.current_test == 64 {
+                    self.palette_explorer.next_source();
+                }
+            }
+            Action::MapUp => self.move_map(0, -1),
+            Action::MapDown => self.move_map(0, 1),
+            Action::MapLeft => self.move_map(-1, 0),
+            Action::MapRight => self.move_map(1, 0),
+            Action::SubtileUp => self.move_subtile(0, -1),
+            Action::SubtileDown => self.move_subtile(0, 1),
+            Action::SubtileLeft => self.move_subtile(-1, 0),
+            Action::SubtileRight => self.move_subtile(1, 0),
+            Action::ToggleHud => self.hud.visible = !self.hud.visible,
+            Action::ToggleGrid => self.grid = !self.grid,
+            Action::ToggleLoupe => self.loupe = !self.loupe,
+            Action::ToggleHelp => self.help = !self.help,
+            Action::ToggleOverview => self.overview.toggle(),
+            Action::ToggleCompare => {
+                self.compare.open = !self.compare.open;
+                //baselines may have been updated since it was last open
+                self.compare.clear_cache();
+            }
+            Action::NextCompareMode => self.compare.next_mode(),
+            Action::CompareOtherPrevious => self.compare.step_other(false, self.test_count()),
+            Action::CompareOtherNext => self.compare.step_other(true, self.test_count()),
+            Action::Screenshot => self.screenshot_requested = true,
+            Action::ToggleRecording => {
+                if self.replay.is_none() {
+                    match &mut self.input_recorder {
+                        Some(recorder) => recorder.stopping = true,
+                        None => {
+                            self.set_time(0.0);
+                            self.input_recorder = Some(InputRecorder::new(self.current_test));
+                            status!("Recording input, press R again to stop");
+                        }
+                    }
+                }
+            }
+            Action::ResetSavedState => self.reset_prefs(),
+            Action::Quit => self.quit_requested = true,
+        }
+    }
+
+    /// Go to the first test of the next or previous category
+    fn step_category(&mut self, forward: bool) {
+        let count = self.test_count();
+        let category = Category::of(self.current_test).step(forward, count);
+        if let Some(test) = category.first_test(count) {
+            self.current_test = test;
+        }
+    }
+
+    fn move_map(&mut self, x: i32, y: i32) {
+        self.map_center.x = self.map_center.x.saturating_add_signed(x);
+        self.map_center.y = self.map_center.y.saturating_add_signed(y);
+        self.tilemap2.center_on(self.map_center)
+    }
+
+    fn move_subtile(&mut self, x: i16, y: i16) {
+        let (current_x, current_y) = self.tilemap2.subtile_offset();
+        self.tilemap2.set_subtile_offset((
+            (current_x + x).clamp(-16, 16),
+            (current_y + y).clamp(-16, 16),
+        ));
+    }
+
+    fn animate(&mut self, fixed_time_step: f64) {
+        self.fast.update(fixed_time_step as f32);
+        self.slow.update(fixed_time_step as f32);
+        self.ici_slow.update(fixed_time_step);
+        self.ici_fast.update(fixed_time_step);
+    }
+
+    /// Put every animation back to its starting state then run the same updates the window
+    /// would for `seconds`, so headless renders of animated tests are repeatable
+    fn set_time(&mut self, seconds: f64) {
+        self.fast.reset();
+        self.slow.reset();
+        self.ici_slow.reset();
+        self.ici_fast.reset();
+        let step = 1.0 / UPDATES_PER_SECOND as f64;
+        for _ in 0..(seconds * UPDATES_PER_SECOND as f64).round() as usize {
+            self.animate(step);
+        }
+    }
+
+    /// Render each of `tests` offscreen at `width`x`height` and pass it to `each`, stopping at the
+    /// first error `each` returns
+    ///
+    /// Panics are caught without being printed so one broken test doesn't stop the rest. With
+    /// `seconds` the animations are set to that time before each test, otherwise they're left
+    /// as they are.
+    fn render_tests(
+        &mut self,
+        tests: impl IntoIterator<Item = usize>,
+        (width, height): (usize, usize),
+        seconds: Option<f64>,
+        mut each: impl FnMut(Rendered) -> Result<()>,
+    ) -> Result<()> {
+        let mut buffer = Graphics::create_buffer_u8(width, height);
+        let mut graphics = Graphics::new_u8_rgba(&mut buffer, width, height)?;
+
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let mut result = Ok(());
+        for test in tests {
+            self.current_test = test;
+            if let Some(seconds) = seconds {
+                self.set_time(seconds);
+            }
+            let start = Instant::now();
+            let rendered = checks::catch(panic::AssertUnwindSafe(|| self.render(&mut graphics)));
+            let time = start.elapsed();
+            let title = match current_title() {
+                title if title.is_empty() => format!("{test}) Untitled"),
+                title => title,
+            };
+            let image = rendered.map(|()| graphics.copy_to_image());
+            result = each(Rendered {
+                test,
+                title,
+                time,
+                image,
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+        panic::set_hook(default_hook);
+        result
+    }
+
+    fn apply_input(&mut self, event: &InputEvent) {
+        match event {
+            InputEvent::MouseMove(x, y) => {
+                if let Some(recorder) = &mut self.input_recorder {
+                    recorder.add(event.clone());
+                }
+                self.mouse_xy = Coord::new(*x, *y);
+            }
+            //recorded by handle_keys, which knows which keys shouldn't be
+            InputEvent::KeyUp(name) => match input::parse_key(name) {
+                Some(key) => self.handle_keys(vec![key]),
+                None => eprintln!("Unknown key in recording: {name}"),
+            },
+        }
+    }
+
+    /// Handle released keys, whether real, replayed or from the control channel
+    fn handle_keys(&mut self, keys: Vec<KeyCode>) {
+        if self.overview.open {
+            for key in keys {
+                if self.bindings.is_bound(Action::ToggleOverview, key)
+                    || self.bindings.is_bound(Action::Quit, key)
+                {
+                    self.overview.open = false;
+                } else if let Some(test) = self.overview.on_key(key) {
+                    self.current_test = test;
+                }
+            }
+            return;
+        }
+        if self.compare.open
+            && keys
+                .iter()
+                .any(|key| self.bindings.is_bound(Action::Quit, *key))
+        {
+            self.compare.open = false;
+            return;
+        }
+        if let Some(recorder) = &mut self.input_recorder {
+            for key in keys
+                .iter()
+                .filter(|key| !self.bindings.is_bound(Action::ToggleRecording, **key))
+            {
+                recorder.add(InputEvent::KeyUp(input::key_name(*key)));
+            }
+        }
+        for action in self.bindings.actions_for(&keys) {
+            self.perform(action);
+        }
+    }
+
+    /// Run any commands from the control channel
+    fn update_control(&mut self) {
+        let Some(control) = &self.control else {
+            return;
+        };
+        for request in control.pending() {
+            let result = match &request.command {
+                Command::Goto(test) if *test < self.test_count() => {
+                    self.overview.open = false;
+                    self.current_test = *test;
+                    Ok(String::new())
+                }
+                Command::Goto(_) => Err(format!("there are only {} tests", self.test_count())),
+                Command::SetTime(seconds) => {
+                    self.set_time(*seconds);
+                    self.time_frozen = true;
+                    Ok(String::new())
+                }
+                Command::Resume => {
+                    self.time_frozen = false;
+                    Ok(String::new())
+                }
+                Command::Mouse(x, y) => {
+                    self.apply_input(&InputEvent::MouseMove(*x, *y));
+                    Ok(String::new())
+                }
+                Command::Key(key) => {
+                    self.apply_input(&InputEvent::KeyUp(input::key_name(*key)));
+                    Ok(String::new())
+                }
+                Command::Screenshot(path) => {
+                    //replied to once the next frame is saved
+                    let path = path.clone();
+                    if let Some((_, earlier)) = self.control_screenshot.take() {
+                        earlier.reply(Err(String::from("replaced by a later screenshot")));
+                    }
+                    self.control_screenshot = Some((path, request));
+                    continue;
+                }
+                Command::Status => Ok(match current_title() {
+                    title if title.is_empty() => self.current_test.to_string(),
+                    title => title,
+                }),
+                Command::Quit => {
+                    self.quit_requested = true;
+                    Ok(String::new())
+                }
+            };
+            request.reply(result);
+        }
+    }
+
+    fn save_control_screenshot(&mut self, graphics: &Graphics) {
+        if let Some((path, request)) = self.control_screenshot.take() {
+            let result = capture::save_png(&path, &graphics.copy_to_image())
+                .map(|_| path.display().to_string())
+                .map_err(|e| format!("{e:#}"));
+            request.reply(result);
+        }
+    }
+
+    fn update_replay(&mut self) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        if replay.finished {
+            return;
+        }
+        let events = replay.update();
+        let reached_end = replay.reached_end();
+        let end = replay.end();
+        for event in &events {
+            self.apply_input(event);
+        }
+        if reached_end {
+            //snap back to the exact time the recording stopped, the window may run a few extra updates
+            self.set_time(end);
+            if let Some(replay) = &mut self.replay {
+                replay.finished = true;
+            }
+        }
+    }
+
+    /// Save the input recording if it's being stopped, or check the replay if it just ended
+    fn finish_input_recording(&mut self, graphics: &Graphics) {
+        if self.input_recorder.as_ref().map(|r| r.stopping).unwrap_or(false) {
+            if let Some(recorder) = self.input_recorder.take() {
+                match recorder.save(&graphics.copy_to_image()) {
+                    Ok(path) => status!("Saved {}", path.display()),
+                    Err(e) => eprintln!("Saving recording failed: {e:?}"),
+                }
+            }
+        }
+        if let Some(replay) = &self.replay {
+            if replay.finished {
+                input::check_frame(replay.frame_hash(), &graphics.copy_to_image());
+                self.replay = None;
+            }
+        }
+    }
+
+    /// Draw `test` offscreen, leaving the current test unchanged
+    fn render_side(&mut self, test: usize) -> Side {
+        let mut buffer = Graphics::create_buffer_u8(SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize);
+        let frame =
+            Graphics::new_u8_rgba(&mut buffer, SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize)
+                .ok()
+                .map(|mut graphics| {
+                    let current = self.current_test;
+                    self.current_test = test;
+                    self.draw_test(&mut graphics);
+                    self.current_test = current;
+                    graphics.copy_to_image()
+                });
+        let label = match current_title() {
+            title if title.is_empty() => test.to_string(),
+            title => title,
+        };
+        Side { label, frame }
+    }
+
+    fn render_compare(&mut self, graphics: &mut Graphics) {
+        let test = self.current_test;
+        let time = match self.compare.mode {
+            compare::Mode::Tests => {
+                let other = self.compare.other_test.min(self.test_count() - 1);
+                let left = self.render_side(test);
+                let right = self.render_side(other);
+                self.compare.render(graphics, &left, &right, self.mouse_xy);
+                return;
+            }
+            compare::Mode::Times => self.compare.other_time,
+            compare::Mode::Baseline => 0.0,
+        };
+        if self.compare.cached(test, time).is_none() {
+            //put back afterwards so the window's animations (or a held `set-time`) carry on as before
+            let animations = (
+                self.fast.clone(),
+                self.slow.clone(),
+                self.ici_slow.clone(),
+                self.ici_fast.clone(),
+            );
+            //both start from the same state as headless renders
+            self.set_time(0.0);
+            let mut left = self.render_side(test);
+            let right = if self.compare.mode == compare::Mode::Times {
+                left.label = format!("0.000s {}", left.label);
+                self.set_time(time);
+                let mut right = self.render_side(test);
+                right.label = format!("{time:.3}s {}", right.label);
+                right
+            } else {
+                let stem = capture::file_stem(test, &left.label);
+                let path = Path::new(snapshot::DEFAULT_BASELINES).join(format!("{stem}.png"));
+                match snapshot::load_png(&path) {
+                    Ok(baseline) => Side {
+                        label: format!("Baseline {}", path.display()),
+                        frame: Some(baseline),
+                    },
+                    Err(_) => Side {
+                        label: format!("No baseline {}", path.display()),
+                        frame: None,
+                    },
+                }
+            };
+            (self.fast, self.slow, self.ici_slow, self.ici_fast) = animations;
+            self.compare.cache(test, time, left, right);
+        }
+        if let Some((left, right)) = self.compare.cached(test, time) {
+            self.compare.render(graphics, left, right, self.mouse_xy);
+        }
+    }
+
+    /// Draw the current test without any overlays
+    fn draw_test(&mut self, graphics: &mut Graphics) {
+        TITLE.with_borrow_mut(|title| title.clear());
         graphics.clear(BLACK);
         match self.current_test {
             0 => test_0(graphics),
@@ -181,6 +773,27 @@ impl System for Example {
             59 => test_font(graphics, PixelFont::Limited3x5, "59) Limited 3x5"),
             60 => test_60(graphics, &self.tilemap),
             61 => test_61(graphics, &self.tilemap2, &self.map_center),
+            62 => test_62(graphics, &self.ici_tileset),
+            63 => test_63(graphics),
+            64 => test_64(graphics, &self.palette_explorer),
+            65..=69 => {
+                let count = self.stress.counts[self.current_test - Stress::FIRST];
+                let start = Instant::now();
+                let objects = match self.current_test {
+                    65 => test_65(graphics, count),
+                    66 => test_66(graphics, count, self.fast.value_int()),
+                    67 => test_67(graphics, count),
+                    68 => test_68(graphics, count, &self.ici_static),
+                    _ => test_69(graphics, count),
+                };
+                self.stress.render_time = start.elapsed();
+                draw_stress_overlay(graphics, &self.stress, objects);
+            }
+            n if n < self.test_count() => {
+                let scene = &self.scenes[n - TEST_COUNT];
+                draw_title(graphics, &format!("{n}) {}", scene.title));
+                scene.render(graphics);
+            }
             _ => graphics.draw_text(
                 &format!("Unknown test: {}", self.current_test),
                 CENTER.textpos(),
@@ -189,45 +802,388 @@ impl System for Example {
         }
     }
 
-    fn on_key_up(&mut self, keys: Vec<KeyCode>) {
-        if keys.contains(&KeyCode::ArrowRight) {
-            self.current_test += 1;
-        } else if keys.contains(&KeyCode::ArrowLeft) {
-            if self.current_test > 0 {
-                self.current_test -= 1;
+    fn save_screenshot(&self, graphics: &Graphics) {
+        let stem = capture::file_stem(self.current_test, &current_title());
+        match capture::save_screenshot(Path::new(capture::SCREENSHOT_DIR), &stem, graphics) {
+            Ok((paths, skipped)) => {
+                for path in paths {
+                    status!("Saved {}", path.display());
+                }
+                if let Some(reason) = skipped {
+                    status!("{reason}");
+                }
             }
-        } else if keys.contains(&KeyCode::Space) {
-            self.current_test = 61;
-        } else if keys.contains(&KeyCode::Escape) {
+            Err(e) => eprintln!("Screenshot failed: {e:?}"),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let (common, args) = cli::Common::extract(std::env::args().skip(1).collect())?;
+    let rest = args.get(1..).unwrap_or_default();
+    match args.first().map(|arg| arg.as_str()) {
+        None => window(&common, None),
+        Some("show") => show(&common, rest),
+        Some("list") => {
+            common.only("list", &[])?;
+            cli::list()
+        }
+        Some("render") => render(&common, rest),
+        Some("validate-assets") => {
+            common.only("validate-assets", &[])?;
+            validate_assets()
+        }
+        Some("verify") => verify(&common, rest),
+        Some("fuzz") => fuzz(&common, rest),
+        Some("bench") => bench(&common, rest),
+        Some("record") => record(&common, rest),
+        Some("replay") => replay(&common, rest),
+        Some("check") => check(&common, rest),
+        Some("help" | "--help" | "-h") => {
+            print!("{}", cli::USAGE);
+            Ok(())
+        }
+        Some(other) => anyhow::bail!("unknown command: {other}\n\n{}", cli::USAGE),
+    }
+}
+
+/// Open the window at `test`, or the last viewed test
+fn window(common: &cli::Common, test: Option<usize>) -> Result<()> {
+    common.only("the window", &["--scale", "--size", "--control"])?;
+    let mut system = Example::new();
+    system.restore_prefs();
+    if let Some(test) = test {
+        system.current_test = test;
+    }
+    if let Some(target) = &common.control {
+        system.control = Some(Control::open(target)?);
+    }
+    run(
+        common.width,
+        common.height,
+        "Testing",
+        Box::new(system),
+        common.window_options()?,
+    )?;
+    Ok(())
+}
+
+/// Parse `show <test>`
+fn show(common: &cli::Common, args: &[String]) -> Result<()> {
+    let [spec] = args else {
+        anyhow::bail!("show requires one test number or name");
+    };
+    let titles = cli::titles(&mut Example::new())?;
+    let test = cli::select_one(spec, &titles)?;
+    window(common, Some(test))
+}
+
+/// Parse `render <tests> [--out DIR] [--time SECONDS]`
+fn render(common: &cli::Common, args: &[String]) -> Result<()> {
+    common.only("render", &["--scale", "--size"])?;
+    let mut spec = None;
+    let mut output = PathBuf::from(cli::DEFAULT_RENDER_DIR);
+    let mut seconds = 0.0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("{arg} requires a value"));
+        match arg.as_str() {
+            "--out" => output = PathBuf::from(value()?),
+            "--time" => seconds = value()?.parse()?,
+            _ if arg.starts_with("--") => anyhow::bail!("unknown render argument: {arg}"),
+            _ if spec.is_some() => anyhow::bail!("render takes one test, category or `all`"),
+            _ => spec = Some(arg.as_str()),
+        }
+    }
+    let spec = spec.ok_or_else(|| anyhow::anyhow!("render requires a test, category or `all`"))?;
+    cli::render(common, spec, seconds, &output)
+}
+
+fn validate_assets() -> Result<()> {
+    let suite = junit::asset_suite(assets::check_all());
+    let failed = suite.errors();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} asset(s) failed to load", suite.cases.len());
+    }
+    Ok(())
+}
+
+/// Parse `verify [suite] [--all]`
+fn verify(common: &cli::Common, args: &[String]) -> Result<()> {
+    common.only("verify", &["--seed"])?;
+    let mut filter = None;
+    let mut print_all = false;
+    for arg in args {
+        if arg == "--all" {
+            print_all = true;
+        } else if arg.starts_with("--") {
+            anyhow::bail!("unknown verify argument: {arg}");
+        } else if filter.is_some() {
+            anyhow::bail!("verify takes one suite name");
+        } else {
+            filter = Some(arg.as_str());
+        }
+    }
+    checks::run(filter, common.seed, print_all)
+}
+
+/// Parse `fuzz [--cases N]`
+fn fuzz(common: &cli::Common, args: &[String]) -> Result<()> {
+    common.only("fuzz", &["--seed"])?;
+    let mut cases = fuzz::DEFAULT_CASES;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("{arg} requires a value"));
+        match arg.as_str() {
+            "--cases" => cases = value()?.parse()?,
+            _ => anyhow::bail!("unknown fuzz argument: {arg}"),
+        }
+    }
+    fuzz::run(common.seed, cases)
+}
+
+/// Parse `bench [--iterations N] [--out FILE] [--compare FILE] [--threshold PERCENT]`
+fn bench(common: &cli::Common, args: &[String]) -> Result<()> {
+    common.only("bench", &["--size"])?;
+    let mut iterations = bench::DEFAULT_ITERATIONS;
+    let mut output = PathBuf::from(bench::DEFAULT_OUTPUT);
+    let mut compare = None;
+    let mut threshold = bench::DEFAULT_THRESHOLD;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("{arg} requires a value"));
+        match arg.as_str() {
+            "--iterations" => iterations = value()?.parse()?,
+            "--out" => output = PathBuf::from(value()?),
+            "--compare" => compare = Some(PathBuf::from(value()?)),
+            "--threshold" => threshold = value()?.parse()?,
+            _ => anyhow::bail!("unknown bench argument: {arg}"),
+        }
+    }
+    bench::run(iterations, &output, compare.as_deref(), threshold, common.size())
+}
+
+/// Parse `record <test> [--frames N] [--fps N] [--format gif|apng|ica] [--out DIR]`
+fn record(common: &cli::Common, args: &[String]) -> Result<()> {
+    common.only("record", &["--scale", "--size"])?;
+    let mut test = None;
+    let mut frames = None;
+    let mut fps = record::DEFAULT_FPS;
+    let mut format = record::Format::Gif;
+    let mut output = PathBuf::from(capture::SCREENSHOT_DIR);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("{arg} requires a value"));
+        match arg.as_str() {
+            "--frames" => frames = Some(value()?.parse()?),
+            "--fps" => fps = value()?.parse()?,
+            "--format" => format = value()?.parse()?,
+            "--out" => output = PathBuf::from(value()?),
+            _ if arg.starts_with("--") => anyhow::bail!("unknown record argument: {arg}"),
+            _ if test.is_some() => anyhow::bail!("record takes one test number"),
+            _ => test = Some(arg.parse()?),
+        }
+    }
+    let test = test.ok_or_else(|| anyhow::anyhow!("record requires a test number"))?;
+    record::run(common, test, frames, fps, format, &output)
+}
+
+/// Parse `replay <file> [--window] [--out PNG]`
+fn replay(common: &cli::Common, args: &[String]) -> Result<()> {
+    let mut file = None;
+    let mut window = false;
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--window" => window = true,
+            "--out" => {
+                output = Some(PathBuf::from(
+                    args.next().ok_or_else(|| anyhow::anyhow!("--out requires a value"))?,
+                ))
+            }
+            _ if arg.starts_with("--") => anyhow::bail!("unknown replay argument: {arg}"),
+            _ if file.is_some() => anyhow::bail!("replay takes one recording file"),
+            _ => file = Some(PathBuf::from(arg)),
+        }
+    }
+    let file = file.ok_or_else(|| anyhow::anyhow!("replay requires a recording file"))?;
+    if !window {
+        common.only("replay", &[])?;
+        return input::replay_headless(Recording::load(&file)?, output.as_deref());
+    }
+    common.only("replay --window", &["--scale", "--size"])?;
+    let recording = Recording::load(&file)?;
+
+    let mut system = Example::new();
+    system.current_test = recording.start_test;
+    system.replay = Some(Replay::new(recording));
+    run(
+        common.width,
+        common.height,
+        "Testing",
+        Box::new(system),
+        common.window_options()?,
+    )?;
+    Ok(())
+}
+
+/// Parse `check [--baselines DIR] [--out DIR] [--update] [--junit FILE] [--include NAMES]
+/// [--exclude NAMES]`
+fn check(common: &cli::Common, args: &[String]) -> Result<()> {
+    common.only("check", &["--seed", "--size"])?;
+    let mut baselines = PathBuf::from(snapshot::DEFAULT_BASELINES);
+    let mut output = PathBuf::from(snapshot::DEFAULT_OUTPUT);
+    let mut update = false;
+    let mut junit = None;
+    let mut filter = Filter::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("{arg} requires a value"));
+        match arg.as_str() {
+            "--baselines" => baselines = PathBuf::from(value()?),
+            "--out" => output = PathBuf::from(value()?),
+            "--update" => update = true,
+            "--junit" => junit = Some(PathBuf::from(value()?)),
+            "--include" => filter.include.extend(Filter::parse_names(value()?)?),
+            "--exclude" => filter.exclude.extend(Filter::parse_names(value()?)?),
+            _ => anyhow::bail!("unknown check argument: {arg}"),
+        }
+    }
+    snapshot::run(&baselines, &output, update, junit.as_deref(), &filter, common)
+}
+
+impl System for Example {
+    fn keys_used(&self) -> &[KeyCode] {
+        //the overview search box accepts any letter
+        &ALL_KEYS
+    }
+
+    fn update(&mut self, timing: &Timing, window: &Window) {
+        //the library only saves the window placement when it's closed, not when quitting with Escape
+        if self.quit_requested {
+            if let Some(mut window_prefs) = self.window_prefs() {
+                window_prefs.store(window);
+                if let Err(e) = window_prefs.save() {
+                    eprintln!("Unable to save window prefs: {e:?}");
+                }
+            }
+            self.save_prefs();
             self.should_quit = true;
-        } else if keys.contains(&KeyCode::KeyW) {
-            self.map_center.y=self.map_center.y.saturating_sub(1);
-            self.tilemap2.center_on(self.map_center)
-        } else if keys.contains(&KeyCode::KeyS) {
-            self.map_center.y += 1;
-            self.tilemap2.center_on(self.map_center)
-        } else if keys.contains(&KeyCode::KeyA) {
-            self.map_center.x=self.map_center.x.saturating_sub(1);
-            self.tilemap2.center_on(self.map_center)
-        } else if keys.contains(&KeyCode::KeyD) {
-            self.map_center.x += 1;
-            self.tilemap2.center_on(self.map_center)
-        } else if keys.contains(&KeyCode::KeyI) {
-            let y = (self.tilemap2.subtile_offset().1 - 1).clamp(-16,16);
-            let x = self.tilemap2.subtile_offset().0;
-            self.tilemap2.set_subtile_offset((x,y));
-        } else if keys.contains(&KeyCode::KeyK) {
-            let y = (self.tilemap2.subtile_offset().1 + 1).clamp(-16,16);
-            let x = self.tilemap2.subtile_offset().0;
-            self.tilemap2.set_subtile_offset((x,y));
-        } else if keys.contains(&KeyCode::KeyJ) {
-            let x = (self.tilemap2.subtile_offset().0 - 1).clamp(-16,16);
-            let y = self.tilemap2.subtile_offset().1;
-            self.tilemap2.set_subtile_offset((x,y));
-        } else if keys.contains(&KeyCode::KeyL) {
-            let x = (self.tilemap2.subtile_offset().0 + 1).clamp(-16,16);
-            let y = self.tilemap2.subtile_offset().1;
-            self.tilemap2.set_subtile_offset((x,y));
+            return;
+        }
+        self.update_control();
+        //fixed step so the animations match headless renders and input replays
+        if !self.time_frozen {
+            self.animate(timing.fixed_time_step);
+        }
+        self.palette_explorer.update(timing.delta as f32);
+        self.stress.update(timing.delta);
+        self.hud.update(timing);
+        if let Some(recorder) = &mut self.input_recorder {
+            recorder.update();
+        }
+        self.update_replay();
+    }
+
+    fn on_mouse_move(&mut self, mouse: &MouseData) {
+        if self.replay.is_none() {
+            self.apply_input(&InputEvent::MouseMove(mouse.xy.x, mouse.xy.y));
+        }
+    }
+
+    fn on_mouse_up(&mut self, mouse: &MouseData, button: MouseButton) {
+        if self.replay.is_some() {
+            return;
+        }
+        if self.overview.open && button == MouseButton::Left {
+            if let Some(test) = self.overview.test_at(mouse.xy) {
+                self.overview.open = false;
+                self.current_test = test;
+            }
+        }
+    }
+
+    fn on_scroll(&mut self, _: &MouseData, _: isize, y_diff: isize) {
+        if self.replay.is_some() {
+            return;
+        }
+        if self.overview.open {
+            self.overview.scroll_by(-y_diff.signum());
+        } else if self.compare.open {
+            self.compare.pan_by(-y_diff.signum());
+        } else if self.current_test == 64 {
+            self.palette_explorer.adjust(y_diff as f32);
+        }
+    }
+
+    fn on_key_down(&mut self, keys: Vec<KeyCode>) {
+        if self.replay.is_some() {
+            return;
+        }
+        if self.current_test == 64 && !self.overview.open {
+            let actions = self.bindings.actions_for(&keys);
+            if actions.contains(&Action::Increase) {
+                self.palette_explorer.threshold_change = PaletteExplorer::SPEED;
+            } else if actions.contains(&Action::Decrease) {
+                self.palette_explorer.threshold_change = -PaletteExplorer::SPEED;
+            }
+        }
+    }
+
+    fn render(&mut self, graphics: &mut Graphics) {
+        if self.overview.open {
+            self.overview.render(graphics, self.mouse_xy);
+            self.save_control_screenshot(graphics);
+            return;
+        }
+        if self.compare.open {
+            self.render_compare(graphics);
+            self.save_control_screenshot(graphics);
+            return;
+        }
+        let start = Instant::now();
+        self.draw_test(graphics);
+        self.hud.set_render_time(start.elapsed());
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+            self.save_screenshot(graphics);
+        }
+        self.save_control_screenshot(graphics);
+        self.finish_input_recording(graphics);
+        //copied before the grid is drawn so it doesn't show in the loupe
+        let frame = self.loupe.then(|| graphics.copy_to_image());
+        if self.grid {
+            overlay::draw_grid(graphics);
+        }
+        if let Some(frame) = frame {
+            overlay::draw_loupe(graphics, &frame, self.mouse_xy);
+        }
+        if self.hud.visible {
+            self.hud.render(graphics);
+        }
+        if self.help {
+            self.bindings.render_help(graphics);
+        }
+    }
+
+    fn on_key_up(&mut self, mut keys: Vec<KeyCode>) {
+        //real input would change what's being replayed, so only quitting is allowed
+        if self.replay.is_some() {
+            keys.retain(|key| self.bindings.is_bound(Action::Quit, *key));
+        }
+        self.handle_keys(keys);
+    }
+
+    fn on_window_closed(&mut self) {
+        self.save_prefs();
+    }
+
+    fn window_prefs(&mut self) -> Option<WindowPreferences> {
+        if self.prefs.is_some() && !self.window_reset {
+            prefs::window_prefs()
+        } else {
+            None
         }
     }
 
@@ -236,6 +1192,10 @@ impl System for Example {
     }
 }
 
+/// Matches the default of [Options::ups], used when animating without a window
+const UPDATES_PER_SECOND: usize = 240;
+/// Tests are numbered `0..TEST_COUNT`
+const TEST_COUNT: usize = 70;
 const SCREEN_WIDTH: isize = 250;
 const SCREEN_HEIGHT: isize = 250;
 const HALF_WIDTH: isize = SCREEN_WIDTH / 2;
@@ -251,7 +1211,17 @@ const QUAD_TR: Coord = Coord::new(SCREEN_WIDTH / 4 * 3, SCREEN_HEIGHT / 4);
 const QUAD_BL: Coord = Coord::new(SCREEN_WIDTH / 4, SCREEN_HEIGHT / 4 * 3);
 const QUAD_BR: Coord = Coord::new(SCREEN_WIDTH / 4 * 3, SCREEN_HEIGHT / 4 * 3);
 
+thread_local! {
+    /// Text passed to [draw_title] during the current render
+    static TITLE: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn current_title() -> String {
+    TITLE.with_borrow(|title| title.clone())
+}
+
 fn draw_title(graphics: &mut Graphics, text: &str) {
+    TITLE.with_borrow_mut(|title| *title = text.to_string());
     graphics.draw_text(
         text,
         TextPos::px((HALF_WIDTH, 2)),
@@ -261,6 +1231,26 @@ fn draw_title(graphics: &mut Graphics, text: &str) {
     graphics.draw_line((0, 12), (SCREEN_WIDTH, 12), WHITE);
 }
 
+/// Every valid tile of `tileset` side by side in name order, so they can be treated as one image
+fn tileset_strip(tileset: &IciTileset) -> Option<IndexedImage> {
+    let (tile_w, tile_h) = tileset.tile_size();
+    let mut names: Vec<&String> = tileset.tiles().keys().collect();
+    names.sort();
+    let tiles: Vec<&Vec<u8>> = names
+        .iter()
+        .map(|name| &tileset.tiles()[*name])
+        .filter(|pixels| pixels.len() == tile_w as usize * tile_h as usize)
+        .collect();
+    let width = u8::try_from(tiles.len() * tile_w as usize).ok()?;
+    let mut pixels = vec![];
+    for y in 0..tile_h as usize {
+        for tile in &tiles {
+            pixels.extend_from_slice(&tile[y * tile_w as usize..(y + 1) * tile_w as usize]);
+        }
+    }
+    IndexedImage::new(width, tile_h, tileset.palette().clone(), pixels).ok()
+}
+
 fn draw_point<P: Into<Coord>>(graphics: &mut Graphics, pos: P) {
     let pos = pos.into();
     graphics.set_pixel(pos.x, pos.y, RED);
@@ -1471,7 +2461,7 @@ fn test_37(graphics: &mut Graphics) {
     graphics.custom_font.insert(
         chr_to_code('b'),
         CustomLetter {
-            _4x5: [true; standard_4x5::LETTER_PX_COUNT],
+            font_4x5: [true; standard_4x5::LETTER_PX_COUNT],
             ..CustomLetter::default()
         },
     );
@@ -1696,4 +2686,326 @@ fn test_61(graphics: &mut Graphics, tilemap: &Tilemap<IndexedImage>, center: &Ma
     graphics.draw_circle(Circle::new(offset+px+(8,8), 8), fill(RED));
 
     graphics.draw_text(&format!("Centered at\n{center:?}"), TextPos::Px(20,30), (WHITE));
-}
\ No newline at end of file
+}
+
+fn test_62(graphics: &mut Graphics, tileset: &IciTileset) {
+    draw_title(graphics, &format!("62) Tileset browser ({})", tileset.name));
+
+    let (tile_w, tile_h) = tileset.tile_size();
+    let palette = tileset.palette();
+    let expected_len = tile_w as usize * tile_h as usize;
+
+    let mut names: Vec<&String> = tileset.tiles().keys().collect();
+    names.sort();
+
+    let cell_w = (tile_w as isize).max(40) + 4;
+    let cell_h = tile_h as isize + 12;
+    let cols = ((SCREEN_WIDTH - 8) / cell_w).max(1);
+    let offset = coord!(4, 16);
+
+    let mut used = vec![false; palette.len()];
+    let mut bad_lengths = vec![];
+
+    for (idx, name) in names.iter().enumerate() {
+        let pixels = &tileset.tiles()[*name];
+        for px in pixels {
+            if let Some(used) = used.get_mut(*px as usize) {
+                *used = true;
+            }
+        }
+
+        let pos = offset + ((idx as isize % cols) * cell_w, (idx as isize / cols) * cell_h);
+        match IndexedImage::new(tile_w, tile_h, palette.clone(), pixels.clone()) {
+            Ok(image) => graphics.draw_indexed_image(pos, &image),
+            Err(_) => {
+                let cross = Rect::new(pos, pos + (tile_w as isize - 1, tile_h as isize - 1));
+                graphics.draw_rect(cross.clone(), stroke(RED));
+                graphics.draw_line(cross.top_left(), cross.bottom_right(), RED);
+                graphics.draw_line(cross.top_right(), cross.bottom_left(), RED);
+            }
+        }
+        if pixels.len() != expected_len {
+            bad_lengths.push(format!("{idx}:{name} {}/{expected_len}", pixels.len()));
+        }
+        graphics.draw_text(
+            &format!("{idx}:{name}"),
+            TextPos::px(pos + (0, tile_h as isize + 2)),
+            (WHITE, PixelFont::Standard4x4),
+        );
+    }
+
+    let unused: Vec<String> = used
+        .iter()
+        .enumerate()
+        .filter(|(_, used)| !**used)
+        .map(|(i, _)| i.to_string())
+        .collect();
+
+    let report_y = offset.y + (names.len() as isize + cols - 1) / cols * cell_h + 8;
+    let report_width = WrappingStrategy::SpaceBeforeCol(PixelFont::Standard4x5.px_to_cols(SCREEN_WIDTH as usize - 8));
+    graphics.draw_text(
+        &format!(
+            "Tile size: {tile_w}x{tile_h}  Palette: {}  Tiles: {}",
+            palette.len(),
+            names.len()
+        ),
+        TextPos::px(coord!(4, report_y)),
+        (LIGHT_GRAY, PixelFont::Standard4x5),
+    );
+    let (text, color) = if unused.is_empty() {
+        (String::from("Unused palette entries: none"), GREEN)
+    } else {
+        (format!("Unused palette entries: {}", unused.join(", ")), YELLOW)
+    };
+    graphics.draw_text(&text, TextPos::px(coord!(4, report_y + 10)), (color, PixelFont::Standard4x5, report_width));
+    let (text, color) = if bad_lengths.is_empty() {
+        (String::from("Wrong pixel lengths: none"), GREEN)
+    } else {
+        (format!("Wrong pixel lengths: {}", bad_lengths.join(", ")), RED)
+    };
+    graphics.draw_text(&text, TextPos::px(coord!(4, report_y + 40)), (color, PixelFont::Standard4x5, report_width));
+}
+
+fn test_63(graphics: &mut Graphics) {
+    draw_title(graphics, "63) Scaling gallery");
+
+    // diamond with a diagonal through it, EPX only changes anything on diagonal edges
+    let mut sprite = IndexedImage::blank(10, 10, vec![TRANSPARENT, WHITE, RED]);
+    for i in 0..5 {
+        for (x, y) in [(4 - i, i), (5 + i, i), (4 - i, 9 - i), (5 + i, 9 - i)] {
+            sprite.set_pixel(x + y * 10, 1).unwrap();
+        }
+        sprite.set_pixel(i * 2 + i * 2 * 10, 2).unwrap();
+    }
+
+    let mut algos = vec![];
+    for (x, y) in [(1, 1), (2, 1), (1, 2), (2, 2), (3, 3), (4, 2), (1, 4)] {
+        algos.push(Scaling::nearest_neighbour(x, y).unwrap());
+    }
+    algos.push(Scaling::Epx2x);
+    algos.push(Scaling::Epx4x);
+
+    let mut pos = coord!(6, 18);
+    let mut row_height = 0;
+    for algo in algos {
+        let (w, h) = checks::scaling::expected_size(10, 10, algo);
+        if pos.x + w as isize > SCREEN_WIDTH - 6 {
+            pos = coord!(6, pos.y + row_height + 16);
+            row_height = 0;
+        }
+        match sprite.scale(algo) {
+            Ok(scaled) => {
+                graphics.draw_rect(
+                    Rect::new_with_size(pos - (1, 1), scaled.width() as usize + 2, scaled.height() as usize + 2),
+                    stroke(DARK_GRAY),
+                );
+                graphics.draw_indexed_image(pos, &scaled);
+                let color = if (scaled.width() as usize, scaled.height() as usize) == (w, h) { WHITE } else { RED };
+                graphics.draw_text(
+                    &format!("{}\n{}x{}", checks::scaling::name(algo), scaled.width(), scaled.height()),
+                    TextPos::px(pos + (0, h as isize + 3)),
+                    (color, PixelFont::Standard4x4),
+                );
+            }
+            Err(e) => graphics.draw_text(
+                &format!("{}\n{e}", checks::scaling::name(algo)),
+                TextPos::px(pos),
+                (RED, PixelFont::Standard4x4),
+            ),
+        }
+        pos.x += w.max(28) as isize + 8;
+        row_height = row_height.max(h as isize);
+    }
+
+    graphics.draw_text(
+        "Non-integer nearest neighbour factors aren't supported, Scaling::nearest_neighbour only takes whole numbers",
+        TextPos::px(coord!(6, SCREEN_HEIGHT - 24)),
+        (LIGHT_GRAY, PixelFont::Standard4x4, WrappingStrategy::SpaceBeforeCol(PixelFont::Standard4x4.px_to_cols(SCREEN_WIDTH as usize - 12))),
+    );
+}
+
+fn test_64(graphics: &mut Graphics, explorer: &PaletteExplorer) {
+    draw_title(graphics, "64) Palette explorer");
+
+    let (name, image) = &explorer.sources[explorer.source];
+    let threshold = explorer.threshold_int();
+    let palette = image.get_palette();
+    let simplified = simplify_palette(palette, threshold);
+    let mut remapped = image.clone();
+    remapped.set_palette(&simplified).unwrap();
+
+    let mut kept: Vec<Color> = vec![];
+    for color in &simplified {
+        if !kept.contains(color) {
+            kept.push(*color);
+        }
+    }
+    let mut total: Vec<Color> = vec![];
+    for color in palette {
+        if !total.contains(color) {
+            total.push(*color);
+        }
+    }
+
+    let mut worst = (0, 0);
+    for (i, px) in image.get_pixels().iter().enumerate() {
+        let diff = palette[*px as usize].diff(&simplified[*px as usize]);
+        if diff > worst.0 {
+            worst = (diff, i);
+        }
+    }
+
+    graphics.draw_text(
+        &format!(
+            "Built-in asset {}/{}: {name} (Tab)\nThreshold: {threshold} (Up/Down, scroll)",
+            explorer.source + 1,
+            explorer.sources.len()
+        ),
+        TextPos::px(coord!(4, 16)),
+        (WHITE, PixelFont::Standard4x5),
+    );
+
+    let draw_swatches = |graphics: &mut Graphics, y: isize, colors: &[Color]| {
+        for (i, color) in colors.iter().enumerate() {
+            let pos = coord!(4 + (i as isize % 30) * 8, y + (i as isize / 30) * 8);
+            graphics.draw_rect(Rect::new_with_size(pos, 7, 7), fill(*color));
+            if color.is_transparent() {
+                graphics.draw_rect(Rect::new_with_size(pos, 7, 7), stroke(DARK_GRAY));
+            }
+        }
+    };
+    graphics.draw_text("Original", TextPos::px(coord!(4, 32)), (LIGHT_GRAY, PixelFont::Standard4x4));
+    draw_swatches(graphics, 38, &total);
+    let kept_y = 38 + ((total.len() as isize + 29) / 30) * 8 + 4;
+    graphics.draw_text(
+        &format!("Kept {} of {} colors", kept.len(), total.len()),
+        TextPos::px(coord!(4, kept_y)),
+        (LIGHT_GRAY, PixelFont::Standard4x4),
+    );
+    draw_swatches(graphics, kept_y + 6, &kept);
+
+    let image_y = kept_y + 6 + ((kept.len() as isize + 29) / 30) * 8 + 4;
+    let factor = (96 / image.width().max(image.height()) as usize).max(1);
+    let scaling = Scaling::nearest_neighbour(factor, factor).unwrap();
+    let (orig, remapped) = match (image.scale(scaling), remapped.scale(scaling)) {
+        (Ok(orig), Ok(remapped)) => (orig, remapped),
+        _ => (image.clone(), remapped),
+    };
+    graphics.draw_indexed_image(coord!(4, image_y), &orig);
+    graphics.draw_indexed_image(coord!(HALF_WIDTH + 4, image_y), &remapped);
+
+    let w = image.width() as usize;
+    graphics.draw_text(
+        &format!(
+            "Worst pixel error: {} at ({},{})",
+            worst.0,
+            worst.1 % w,
+            worst.1 / w
+        ),
+        TextPos::px(coord!(4, SCREEN_HEIGHT - 10)),
+        (if worst.0 == 0 { GREEN } else { YELLOW }, PixelFont::Standard4x5),
+    );
+}
+
+fn stress_color(rng: &mut fastrand::Rng) -> Color {
+    Color::new(rng.u8(..), rng.u8(..), rng.u8(..), 255)
+}
+
+fn stress_coord(rng: &mut fastrand::Rng) -> Coord {
+    Coord::new(rng.isize(0..SCREEN_WIDTH), rng.isize(14..SCREEN_HEIGHT))
+}
+
+/// Frame time, draw time and object count, drawn over the bottom of a stress page
+fn draw_stress_overlay(graphics: &mut Graphics, stress: &Stress, objects: usize) {
+    graphics.draw_rect(Rect::new((0, SCREEN_HEIGHT - 22), (SCREEN_WIDTH, SCREEN_HEIGHT)), fill(BLACK));
+    graphics.draw_text(
+        &format!(
+            "Objects: {objects}\nFrame: {:.1}ms  Draw: {:.1}ms\nUp/Down to change count",
+            stress.frame_time * 1000.0,
+            stress.render_time.as_secs_f64() * 1000.0
+        ),
+        TextPos::px((2, SCREEN_HEIGHT - 21)),
+        (WHITE, PixelFont::Standard4x5),
+    );
+}
+
+fn test_65(graphics: &mut Graphics, count: usize) -> usize {
+    draw_title(graphics, "65) Stress: lines");
+
+    let mut rng = fastrand::Rng::with_seed(Stress::SEED);
+    for _ in 0..count {
+        let start = stress_coord(&mut rng);
+        let end = stress_coord(&mut rng);
+        graphics.draw_line(start, end, stress_color(&mut rng));
+    }
+    count
+}
+
+fn test_66(graphics: &mut Graphics, count: usize, degrees: isize) -> usize {
+    draw_title(graphics, "66) Stress: rotated polygons");
+
+    let mut rng = fastrand::Rng::with_seed(Stress::SEED);
+    for _ in 0..count {
+        let center = stress_coord(&mut rng);
+        let points: Vec<Coord> = (0..rng.usize(3..=6))
+            .map(|_| center + (rng.isize(-10..=10), rng.isize(-10..=10)))
+            .collect();
+        let polygon = Drawable::from_obj(Polygon::new(&points), fill(stress_color(&mut rng)));
+        graphics.draw(&polygon.with_rotation(degrees));
+    }
+    count
+}
+
+fn test_67(graphics: &mut Graphics, count: usize) -> usize {
+    draw_title(graphics, "67) Stress: 4x4 text");
+
+    let font = PixelFont::Standard4x4;
+    let chars: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".chars().collect();
+    let text: String = (0..count).map(|i| chars[i % chars.len()]).collect();
+    graphics.draw_text(
+        &text,
+        TextPos::px((0, 14)),
+        (
+            WHITE,
+            font,
+            WrappingStrategy::AtCol(SCREEN_WIDTH as usize / font.char_width()),
+        ),
+    );
+    count
+}
+
+fn test_68(graphics: &mut Graphics, count: usize, image: &IndexedImage) -> usize {
+    draw_title(graphics, "68) Stress: sprites");
+
+    let mut rng = fastrand::Rng::with_seed(Stress::SEED);
+    for _ in 0..count {
+        let pos = stress_coord(&mut rng) - (image.width() as isize / 2, image.height() as isize / 2);
+        graphics.draw_indexed_image(pos, image);
+    }
+    count
+}
+
+/// Each level is a collection of the level below scaled into each quadrant, plus a border,
+/// collections are flattened when inserted so this counts shapes rather than collections
+fn test_69(graphics: &mut Graphics, depth: usize) -> usize {
+    draw_title(graphics, "69) Stress: nested collections");
+
+    let area = Rect::new((0, 14), (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1));
+    let colors = [RED, GREEN, BLUE, YELLOW, MAGENTA, CYAN, WHITE];
+    let mut collection = ShapeCollection::default();
+    InsertShape::insert_above(&mut collection, area.clone(), stroke(colors[0]));
+    for level in 1..depth {
+        let smaller = collection.with_scale(0.5);
+        let (w, h) = (area.width() as isize / 2, area.height() as isize / 2);
+        let mut next = ShapeCollection::default();
+        InsertShape::insert_above(&mut next, area.clone(), stroke(colors[level % colors.len()]));
+        for offset in [(0, 0), (w, 0), (0, h), (w, h)] {
+            for shape in smaller.with_move(area.top_left() + offset).iter() {
+                InsertDrawable::insert_above(&mut next, shape.clone());
+            }
+        }
+        collection = next;
+    }
+    graphics.draw(&collection);
+    collection.len()
+}
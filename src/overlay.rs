@@ -0,0 +1,100 @@
+//! Inspection overlays drawn over the current test, after any screenshot is taken
+
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use pixels_graphics_lib::prelude::*;
+
+/// Spacing of the grid lines, every [GRID_MAJOR]th line is brighter
+const GRID_STEP: isize = 10;
+const GRID_MAJOR: isize = 5;
+/// Width and height of the area around the mouse shown in the loupe
+const LOUPE_AREA: isize = 15;
+const LOUPE_ZOOM: isize = 6;
+const LOUPE_MARGIN: isize = 4;
+
+/// Pixel grid with brighter lines every 50px
+pub fn draw_grid(graphics: &mut Graphics) {
+    let minor = Color::new(255, 255, 255, 30);
+    let major = Color::new(255, 255, 255, 80);
+    for i in (GRID_STEP..SCREEN_WIDTH.max(SCREEN_HEIGHT)).step_by(GRID_STEP as usize) {
+        let color = if (i / GRID_STEP) % GRID_MAJOR == 0 {
+            major
+        } else {
+            minor
+        };
+        graphics.draw_line((i, 0), (i, SCREEN_HEIGHT), color);
+        graphics.draw_line((0, i), (SCREEN_WIDTH, i), color);
+    }
+}
+
+/// Magnified view of the pixels of `frame` around `mouse`, shown in the corner furthest from it
+pub fn draw_loupe(graphics: &mut Graphics, frame: &Image, mouse: Coord) {
+    let size = LOUPE_AREA * LOUPE_ZOOM;
+    let x = if mouse.x < SCREEN_WIDTH / 2 {
+        SCREEN_WIDTH - size - LOUPE_MARGIN
+    } else {
+        LOUPE_MARGIN
+    };
+    let y = if mouse.y < SCREEN_HEIGHT / 2 {
+        SCREEN_HEIGHT - size - LOUPE_MARGIN - 12
+    } else {
+        LOUPE_MARGIN + 14
+    };
+    let origin = Coord::new(x, y);
+    draw_magnified(graphics, frame, mouse, origin);
+}
+
+/// Draw the [LOUPE_AREA] pixels of `frame` around `center` with their top left at `origin`
+pub fn draw_magnified(graphics: &mut Graphics, frame: &Image, center: Coord, origin: Coord) {
+    let size = LOUPE_AREA * LOUPE_ZOOM;
+    let half = LOUPE_AREA / 2;
+    graphics.draw_rect(
+        Rect::new_with_size(origin - (1, 1), size as usize + 2, size as usize + 2),
+        stroke(WHITE),
+    );
+    for dy in 0..LOUPE_AREA {
+        for dx in 0..LOUPE_AREA {
+            let color = pixel_at(frame, center + (dx - half, dy - half)).unwrap_or(DARK_GRAY);
+            graphics.draw_rect(
+                Rect::new_with_size(
+                    origin + (dx * LOUPE_ZOOM, dy * LOUPE_ZOOM),
+                    LOUPE_ZOOM as usize,
+                    LOUPE_ZOOM as usize,
+                ),
+                fill(color),
+            );
+        }
+    }
+    let middle = origin + (half * LOUPE_ZOOM, half * LOUPE_ZOOM);
+    graphics.draw_rect(
+        Rect::new_with_size(
+            middle - (1, 1),
+            LOUPE_ZOOM as usize + 1,
+            LOUPE_ZOOM as usize + 1,
+        ),
+        stroke(YELLOW),
+    );
+
+    let picked = match pixel_at(frame, center) {
+        Some(color) => format!(
+            "{},{} #{:02X}{:02X}{:02X}{:02X}",
+            center.x, center.y, color.r, color.g, color.b, color.a
+        ),
+        None => format!("{},{}", center.x, center.y),
+    };
+    let text_pos = origin + (0, size + 3);
+    graphics.draw_rect(
+        Rect::new_with_size(text_pos - (1, 1), size as usize + 1, 7),
+        fill(BLACK),
+    );
+    graphics.draw_text(
+        &picked,
+        TextPos::px(text_pos),
+        (WHITE, PixelFont::Standard4x5),
+    );
+}
+
+fn pixel_at(frame: &Image, xy: Coord) -> Option<Color> {
+    let x = usize::try_from(xy.x).ok()?;
+    let y = usize::try_from(xy.y).ok()?;
+    (x < frame.width() && y < frame.height()).then(|| frame.get_pixel(x, y))
+}
@@ -0,0 +1,308 @@
+//! Thumbnail grid of every test
+//!
+//! The thumbnails are rendered once, the first time the overview is opened, from a fresh
+//! [Example] so animated tests match `check`. If [snapshot::DEFAULT_BASELINES] exists each
+//! thumbnail is outlined green or red depending on whether it matches its baseline.
+//! Typing filters the grid by title (or exact category or tag name), clicking a thumbnail (or Enter for the first match) opens it.
+
+use crate::{capture, category, snapshot, Example, SCREEN_HEIGHT, SCREEN_WIDTH};
+use pixels_graphics_lib::prelude::*;
+use std::path::Path;
+
+/// Thumbnails are the screen shrunk by this much
+const SHRINK: usize = 6;
+const THUMB_SIZE: isize = SCREEN_WIDTH / SHRINK as isize;
+const CELL_SIZE: isize = 50;
+const COLUMNS: isize = SCREEN_WIDTH / CELL_SIZE;
+const GRID_TOP: isize = 24;
+const FOOTER_HEIGHT: isize = 10;
+const ROWS: isize = (SCREEN_HEIGHT - GRID_TOP - FOOTER_HEIGHT) / CELL_SIZE;
+/// Characters of the title that fit under a thumbnail
+const LABEL_LEN: usize = 8;
+const MAX_SEARCH_LEN: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Fail,
+    NoBaseline,
+    Panicked,
+}
+
+impl Status {
+    fn color(&self) -> Color {
+        match self {
+            Status::Pass => GREEN,
+            Status::Fail | Status::Panicked => RED,
+            Status::NoBaseline => DARK_GRAY,
+        }
+    }
+}
+
+struct Thumbnail {
+    test: usize,
+    title: String,
+    image: Option<Image>,
+    status: Status,
+}
+
+#[derive(Default)]
+pub struct Overview {
+    pub open: bool,
+    /// Rendered the first time the overview is opened
+    thumbnails: Vec<Thumbnail>,
+    search: String,
+    /// First visible row
+    scroll: isize,
+}
+
+impl Overview {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        if self.open && self.thumbnails.is_empty() {
+            self.thumbnails = render_thumbnails();
+        }
+    }
+
+    /// Indexes into [Self::thumbnails] of those whose title contains the search text, or whose
+    /// category or tag it is
+    fn visible(&self) -> Vec<usize> {
+        let search = self.search.to_lowercase();
+        self.thumbnails
+            .iter()
+            .enumerate()
+            .filter(|(_, thumb)| {
+                thumb.title.to_lowercase().contains(&search) || category::has(thumb.test, &search)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn max_scroll(&self) -> isize {
+        let rows = (self.visible().len() as isize + COLUMNS - 1) / COLUMNS;
+        (rows - ROWS).max(0)
+    }
+
+    pub fn scroll_by(&mut self, rows: isize) {
+        self.scroll = (self.scroll + rows).clamp(0, self.max_scroll());
+    }
+
+    /// Handle a key while open, returns the test to open if one was chosen
+    pub fn on_key(&mut self, key: KeyCode) -> Option<usize> {
+        match key {
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                let first = *self.visible().first()?;
+                self.open = false;
+                return Some(self.thumbnails[first].test);
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::ArrowUp => self.scroll_by(-1),
+            KeyCode::ArrowDown => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-ROWS),
+            KeyCode::PageDown => self.scroll_by(ROWS),
+            _ => {
+                if let Some(c) = key_char(key) {
+                    if self.search.len() < MAX_SEARCH_LEN {
+                        self.search.push(c);
+                    }
+                }
+            }
+        }
+        self.scroll = self.scroll.min(self.max_scroll());
+        None
+    }
+
+    /// Test under `xy`, if any
+    pub fn test_at(&self, xy: Coord) -> Option<usize> {
+        let index = self.cell_at(xy)?;
+        let visible = self.visible();
+        visible.get(index).map(|i| self.thumbnails[*i].test)
+    }
+
+    /// Position in the filtered list of the cell under `xy`
+    fn cell_at(&self, xy: Coord) -> Option<usize> {
+        if xy.x < 0 || xy.x >= COLUMNS * CELL_SIZE || xy.y < GRID_TOP {
+            return None;
+        }
+        let row = (xy.y - GRID_TOP) / CELL_SIZE;
+        if row >= ROWS {
+            return None;
+        }
+        let column = xy.x / CELL_SIZE;
+        Some(((self.scroll + row) * COLUMNS + column) as usize)
+    }
+
+    pub fn render(&self, graphics: &mut Graphics, mouse: Coord) {
+        let font = PixelFont::Standard4x5;
+        graphics.clear(BLACK);
+        let visible = self.visible();
+        graphics.draw_text(
+            &format!(
+                "Overview: {} of {} tests",
+                visible.len(),
+                self.thumbnails.len()
+            ),
+            TextPos::px(coord!(2, 2)),
+            (YELLOW, font),
+        );
+        graphics.draw_rect(Rect::new((1, 10), (248, 20)), stroke(MID_GRAY));
+        let search = if self.search.is_empty() {
+            (String::from("Type to search"), DARK_GRAY)
+        } else {
+            (format!("{}_", self.search), WHITE)
+        };
+        graphics.draw_text(&search.0, TextPos::px(coord!(4, 13)), (search.1, font));
+
+        let hovered = self.cell_at(mouse);
+        for (cell, i) in visible
+            .iter()
+            .enumerate()
+            .skip((self.scroll * COLUMNS) as usize)
+            .take((ROWS * COLUMNS) as usize)
+        {
+            let thumb = &self.thumbnails[*i];
+            let row = cell as isize / COLUMNS - self.scroll;
+            let column = cell as isize % COLUMNS;
+            let origin = coord!(
+                column * CELL_SIZE + (CELL_SIZE - THUMB_SIZE) / 2,
+                GRID_TOP + row * CELL_SIZE + 1
+            );
+            let border = if hovered == Some(cell) {
+                WHITE
+            } else {
+                thumb.status.color()
+            };
+            graphics.draw_rect(
+                Rect::new_with_size(
+                    origin - (1, 1),
+                    THUMB_SIZE as usize + 2,
+                    THUMB_SIZE as usize + 2,
+                ),
+                stroke(border),
+            );
+            match &thumb.image {
+                Some(image) => graphics.draw_image(origin, image),
+                None => graphics.draw_text(
+                    "!",
+                    TextPos::px(origin + (THUMB_SIZE / 2, THUMB_SIZE / 2)),
+                    TextFormat::from((RED, PixelFont::Standard6x7, Positioning::Center)),
+                ),
+            }
+            let label: String = thumb.title.chars().take(LABEL_LEN).collect();
+            graphics.draw_text(
+                &label,
+                TextPos::px(coord!(column * CELL_SIZE + 1, origin.y + THUMB_SIZE + 2)),
+                (LIGHT_GRAY, font),
+            );
+        }
+
+        let footer = hovered
+            .and_then(|cell| visible.get(cell))
+            .map(|i| &self.thumbnails[*i])
+            .map(|thumb| match thumb.status {
+                Status::Pass => format!("{} (matches baseline)", thumb.title),
+                Status::Fail => format!("{} (differs from baseline)", thumb.title),
+                Status::Panicked => format!("{} (panicked)", thumb.title),
+                Status::NoBaseline => thumb.title.clone(),
+            })
+            .unwrap_or_else(|| String::from("Click to open, Enter opens the first match"));
+        graphics.draw_text(
+            &footer,
+            TextPos::px(coord!(2, SCREEN_HEIGHT - FOOTER_HEIGHT + 3)),
+            (WHITE, font),
+        );
+    }
+}
+
+/// Character typed by `key` in the search box
+fn key_char(key: KeyCode) -> Option<char> {
+    let name = format!("{key:?}");
+    let c = name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .or_else(|| name.strip_prefix("Numpad"))
+        .filter(|rest| rest.len() == 1)
+        .and_then(|rest| rest.chars().next());
+    match key {
+        KeyCode::Space => Some(' '),
+        KeyCode::Minus => Some('-'),
+        KeyCode::Period => Some('.'),
+        _ => c.map(|c| c.to_ascii_lowercase()),
+    }
+}
+
+fn render_thumbnails() -> Vec<Thumbnail> {
+    let mut example = Example::new();
+    let baselines = Path::new(snapshot::DEFAULT_BASELINES);
+    let has_baselines = baselines.is_dir();
+
+    let mut thumbnails = vec![];
+    let size = (SCREEN_WIDTH as usize, SCREEN_HEIGHT as usize);
+    //the only error is failing to make the buffer, which leaves the overview empty
+    let _ = example.render_tests(0..example.test_count(), size, None, |rendered| {
+        let Ok(image) = rendered.image else {
+            thumbnails.push(Thumbnail {
+                test: rendered.test,
+                title: rendered.title,
+                image: None,
+                status: Status::Panicked,
+            });
+            return Ok(());
+        };
+        let stem = capture::file_stem(rendered.test, &rendered.title);
+        let baseline = baselines.join(format!("{stem}.png"));
+        let status = if !has_baselines || !baseline.exists() {
+            Status::NoBaseline
+        } else {
+            match snapshot::load_png(&baseline) {
+                Ok(baseline)
+                    if baseline.width() == image.width()
+                        && baseline.height() == image.height()
+                        && snapshot::diff_image(&image, &baseline).1 == 0 =>
+                {
+                    Status::Pass
+                }
+                _ => Status::Fail,
+            }
+        };
+        thumbnails.push(Thumbnail {
+            test: rendered.test,
+            title: rendered.title,
+            image: shrink(&image),
+            status,
+        });
+        Ok(())
+    });
+    thumbnails
+}
+
+/// `image` scaled down by [SHRINK], each pixel the average of the block it covers
+fn shrink(image: &Image) -> Option<Image> {
+    let width = image.width() / SHRINK;
+    let height = image.height() / SHRINK;
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0usize; 4];
+            for dy in 0..SHRINK {
+                for dx in 0..SHRINK {
+                    let color = image.get_pixel(x * SHRINK + dx, y * SHRINK + dy);
+                    sum[0] += color.r as usize;
+                    sum[1] += color.g as usize;
+                    sum[2] += color.b as usize;
+                    sum[3] += color.a as usize;
+                }
+            }
+            let count = SHRINK * SHRINK;
+            pixels.push(Color::new(
+                (sum[0] / count) as u8,
+                (sum[1] / count) as u8,
+                (sum[2] / count) as u8,
+                (sum[3] / count) as u8,
+            ));
+        }
+    }
+    Image::new(pixels, width, height).ok()
+}
@@ -0,0 +1,40 @@
+//! Window placement and tester state restored between runs of the window
+//!
+//! Only the interactive window uses these, headless commands always start from test 0.
+
+use pixels_graphics_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const QUALIFIER: &str = "app";
+const ORGANIZATION: &str = "emmabritton";
+const APPLICATION: &str = "graphics_tester";
+/// Bump when the window pref format changes so old files are ignored
+const WINDOW_PREFS_VERSION: usize = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub current_test: usize,
+    pub grid: bool,
+    pub loupe: bool,
+    pub hud: bool,
+}
+
+pub fn window_prefs() -> Option<WindowPreferences> {
+    WindowPreferences::new(QUALIFIER, ORGANIZATION, APPLICATION, WINDOW_PREFS_VERSION)
+        .map_err(|e| eprintln!("Unable to open window prefs: {e:?}"))
+        .ok()
+}
+
+pub fn app_prefs() -> Option<AppPrefs<Settings>> {
+    AppPrefs::new(QUALIFIER, ORGANIZATION, APPLICATION, Settings::default)
+        .map_err(|e| eprintln!("Unable to open prefs: {e:?}"))
+        .ok()
+}
+
+/// Delete the saved window placement
+pub fn delete_window_prefs() {
+    if let Some(prefs) = window_prefs() {
+        prefs.delete_file();
+    }
+}
@@ -0,0 +1,127 @@
+//! Records animated tests
+//!
+//! Run with `graphics_tester record <test> [--frames N] [--fps N] [--format gif|apng|ica] [--out DIR]`,
+//! by default one full rotation (or animation) cycle is recorded. Frames are rendered offscreen
+//! using [Example::set_time] so recordings are the same every run.
+
+use crate::cli::Common;
+use crate::{capture, current_title, Example, UPDATES_PER_SECOND};
+use anyhow::{anyhow, bail, Result};
+use pixels_graphics_lib::prelude::*;
+use std::path::Path;
+use std::str::FromStr;
+
+pub const ANIMATED_TESTS: [usize; 10] = [14, 15, 16, 17, 21, 24, 26, 30, 31, 32];
+/// Tests that rotate using [Example::slow], the others use [Example::fast]
+const SLOW_TESTS: [usize; 2] = [24, 32];
+pub const DEFAULT_FPS: usize = 25;
+/// Longest cycle that will be recorded when `--frames` isn't given
+const MAX_CYCLE: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Gif,
+    Apng,
+    Ica,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Gif => "gif",
+            Format::Apng => "png",
+            Format::Ica => "ica",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "gif" => Ok(Format::Gif),
+            "apng" => Ok(Format::Apng),
+            "ica" => Ok(Format::Ica),
+            _ => bail!("unknown format {s}, expected gif, apng or ica"),
+        }
+    }
+}
+
+pub fn run(
+    common: &Common,
+    test: usize,
+    frames: Option<usize>,
+    fps: usize,
+    format: Format,
+    output: &Path,
+) -> Result<()> {
+    if !ANIMATED_TESTS.contains(&test) {
+        bail!("test {test} isn't animated, expected one of {ANIMATED_TESTS:?}");
+    }
+    if fps == 0 {
+        bail!("--fps must be at least 1");
+    }
+
+    let mut example = Example::new();
+    example.current_test = test;
+    let count = frames
+        .unwrap_or_else(|| (cycle_seconds(&example, test) * fps as f64).round() as usize)
+        .max(1);
+    if format == Format::Ica && count > u8::MAX as usize {
+        bail!("{count} frames is too many for .ica (max 255), use --frames or a lower --fps");
+    }
+
+    let scaling = Scaling::nearest_neighbour(common.scale, common.scale)
+        .map_err(|e| anyhow!("invalid scale {}: {e:?}", common.scale))?;
+    let mut buffer = Graphics::create_buffer_u8(common.width, common.height);
+    let mut graphics = Graphics::new_u8_rgba(&mut buffer, common.width, common.height)?;
+    let mut images = Vec::with_capacity(count);
+    for i in 0..count {
+        example.set_time(i as f64 / fps as f64);
+        example.render(&mut graphics);
+        let image = graphics.copy_to_image();
+        images.push(if common.scale > 1 {
+            image.scale(scaling)
+        } else {
+            image
+        });
+    }
+
+    std::fs::create_dir_all(output)?;
+    let stem = capture::file_stem(test, &current_title());
+    let path = output.join(format!("{stem}.{}", format.extension()));
+    match format {
+        Format::Gif => capture::save_gif(&path, &images, fps)?,
+        Format::Apng => capture::save_apng(&path, &images, fps)?,
+        Format::Ica => capture::save_ica(&path, &images, fps)?,
+    }
+    println!("Saved {count} frames to {}", path.display());
+    Ok(())
+}
+
+/// Seconds until the test looks the same as it did at the start
+fn cycle_seconds(example: &Example, test: usize) -> f64 {
+    if test == 26 {
+        let period = |image: &AnimatedIndexedImage| {
+            (image.get_per_frame() * image.frame_count() as f64 * 1000.0).round() as usize
+        };
+        let (slow, fast) = (period(&example.ici_slow), period(&example.ici_fast));
+        let lcm = slow / gcd(slow, fast).max(1) * fast;
+        return (lcm as f64 / 1000.0).min(MAX_CYCLE);
+    }
+    let animation = if SLOW_TESTS.contains(&test) {
+        &example.slow
+    } else {
+        &example.fast
+    };
+    (360.0 / (animation.value_change as f64 * UPDATES_PER_SECOND as f64)).min(MAX_CYCLE)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -0,0 +1,88 @@
+//! Self-contained HTML report of a snapshot run, images are embedded so the file can be shared
+//! on its own
+
+use crate::snapshot::{Status, TestResult};
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const STYLE: &str = "body { font-family: sans-serif; background: #222; color: #eee; }
+table { border-collapse: collapse; }
+th, td { padding: 6px; border-bottom: 1px solid #444; text-align: left; vertical-align: top; }
+img { width: 250px; image-rendering: pixelated; background: #000; }
+.pass { color: #6c6; }
+.fail { color: #f66; }
+.missing { width: 250px; color: #888; }";
+
+/// Write the report to `path`, failing tests are listed first
+pub fn write_html(path: &Path, results: &[TestResult]) -> Result<()> {
+    let failed = results.iter().filter(|r| !r.status.passed()).count();
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Graphics tester report</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>"
+    )?;
+    writeln!(
+        html,
+        "<h1>Graphics tester report</h1>\n<p>{} passed, <span class=\"{}\">{failed} failed</span></p>",
+        results.len() - failed,
+        if failed > 0 { "fail" } else { "pass" }
+    )?;
+    writeln!(
+        html,
+        "<table>\n<tr><th>Test</th><th>Status</th><th>Mismatched pixels</th><th>Render time</th><th>Rendered</th><th>Baseline</th><th>Diff</th></tr>"
+    )?;
+    let failures = results.iter().filter(|r| !r.status.passed());
+    let passes = results.iter().filter(|r| r.status.passed());
+    for result in failures.chain(passes) {
+        write_row(&mut html, result)?;
+    }
+    writeln!(html, "</table>\n</body>\n</html>")?;
+    fs::write(path, html).with_context(|| format!("writing {}", path.display()))
+}
+
+fn write_row(html: &mut String, result: &TestResult) -> Result<()> {
+    let (class, status) = if result.status.passed() {
+        ("pass", "PASS")
+    } else {
+        ("fail", "FAIL")
+    };
+    let mismatched = match result.status {
+        Status::Mismatch(count) => count.to_string(),
+        _ => String::from("-"),
+    };
+    writeln!(
+        html,
+        "<tr><td>{}</td><td class=\"{class}\">{status}<br>{}</td><td>{mismatched}</td><td>{:.2}ms</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+        escape(&result.title),
+        escape(&result.status.describe()),
+        result.render_time.as_secs_f64() * 1000.0,
+        embed(result.rendered.as_deref())?,
+        embed(result.baseline.as_deref())?,
+        embed(result.diff.as_deref())?
+    )?;
+    Ok(())
+}
+
+/// `<img>` with the PNG at `path` as a data URI
+fn embed(path: Option<&Path>) -> Result<String> {
+    let Some(path) = path else {
+        return Ok(String::from("<div class=\"missing\">-</div>"));
+    };
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(format!(
+        "<img src=\"data:image/png;base64,{}\" title=\"{}\">",
+        base64::engine::general_purpose::STANDARD.encode(bytes),
+        escape(&path.display().to_string())
+    ))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "<br>")
+}
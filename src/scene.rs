@@ -0,0 +1,233 @@
+//! Visual tests defined in RON files
+//!
+//! Every `.ron` file in [SCENE_DIR] is loaded at startup and shown after the built-in tests, in
+//! file name order. See `scenes/example.ron` for the format, image paths are relative to the
+//! scene file.
+
+use anyhow::{bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SCENE_DIR: &str = "scenes";
+
+type Point = (isize, isize);
+type Rgba = (u8, u8, u8, u8);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scene {
+    pub title: String,
+    #[serde(default)]
+    pub items: Vec<SceneItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum SceneItem {
+    Shape {
+        shape: SceneShape,
+        draw: SceneDraw,
+        /// Applied in order
+        #[serde(default)]
+        transforms: Vec<SceneTransform>,
+    },
+    Text {
+        text: String,
+        pos: Point,
+        color: Rgba,
+        /// Defaults to Standard6x7
+        font: Option<PixelFont>,
+        /// Defaults to LeftTop
+        positioning: Option<Positioning>,
+        /// Defaults to None
+        wrapping: Option<WrappingStrategy>,
+    },
+    Image {
+        /// Path to an `.ici` file
+        path: String,
+        pos: Point,
+        #[serde(skip)]
+        image: Option<IndexedImage>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum SceneShape {
+    Line(Point, Point),
+    Rect(Point, Point),
+    Circle(Point, usize),
+    Ellipse(Point, usize, usize),
+    Triangle(Point, Point, Point),
+    Polygon(Vec<Point>),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum SceneDraw {
+    Fill(Rgba),
+    Stroke(Rgba),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum SceneTransform {
+    Rotation(isize),
+    RotationAround(isize, Point),
+    Scale(f32),
+    ScaleAround(f32, Point),
+    Move(Point),
+    Translation(Point),
+}
+
+fn color((r, g, b, a): Rgba) -> Color {
+    Color::new(r, g, b, a)
+}
+
+impl SceneShape {
+    /// A collection holding just this shape, so transforms can be applied regardless of its type
+    ///
+    /// Rects can't be rotated (they stay axis aligned) so are drawn as polygons when `rotated`
+    fn to_collection(&self, draw_type: DrawType, rotated: bool) -> ShapeCollection {
+        let mut collection = ShapeCollection::default();
+        let shape = match self {
+            SceneShape::Line(start, end) => Line::new(*start, *end).to_shape_box(),
+            SceneShape::Rect(start, end) if rotated => {
+                Rect::new(*start, *end).as_polygon().to_shape_box()
+            }
+            SceneShape::Rect(start, end) => Rect::new(*start, *end).to_shape_box(),
+            SceneShape::Circle(center, radius) => Circle::new(*center, *radius).to_shape_box(),
+            SceneShape::Ellipse(center, w, h) => Ellipse::new(*center, *w, *h).to_shape_box(),
+            SceneShape::Triangle(p1, p2, p3) => Triangle::new(*p1, *p2, *p3).to_shape_box(),
+            SceneShape::Polygon(points) => Polygon::new(points).to_shape_box(),
+        };
+        InsertShapeBox::insert_above(&mut collection, shape, draw_type);
+        collection
+    }
+}
+
+impl Scene {
+    /// Parse a scene and load any images it uses
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut scene: Scene =
+            ron::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for (index, item) in scene.items.iter_mut().enumerate() {
+            match item {
+                SceneItem::Image {
+                    path: image_path,
+                    image,
+                    ..
+                } => {
+                    let image_path = dir.join(&image_path);
+                    let bytes = fs::read(&image_path)
+                        .with_context(|| format!("reading {}", image_path.display()))?;
+                    let (loaded, _) = IndexedImage::from_file_contents(&bytes)
+                        .map_err(|e| anyhow::anyhow!("loading {}: {e:?}", image_path.display()))?;
+                    *image = Some(loaded);
+                }
+                //graphics-shapes panics when drawing these
+                SceneItem::Shape {
+                    shape: SceneShape::Polygon(points),
+                    ..
+                } if points.len() < 3 => bail!(
+                    "checking {}: item {index} is a polygon with {} point(s), it needs at least 3",
+                    path.display(),
+                    points.len()
+                ),
+                _ => {}
+            }
+        }
+        Ok(scene)
+    }
+
+    pub fn render(&self, graphics: &mut Graphics) {
+        for item in &self.items {
+            match item {
+                SceneItem::Shape {
+                    shape,
+                    draw,
+                    transforms,
+                } => {
+                    let draw_type = match draw {
+                        SceneDraw::Fill(rgba) => fill(color(*rgba)),
+                        SceneDraw::Stroke(rgba) => stroke(color(*rgba)),
+                    };
+                    let rotated = transforms.iter().any(|transform| {
+                        matches!(
+                            transform,
+                            SceneTransform::Rotation(_) | SceneTransform::RotationAround(..)
+                        )
+                    });
+                    let mut collection = shape.to_collection(draw_type, rotated);
+                    for transform in transforms {
+                        collection = match transform {
+                            SceneTransform::Rotation(degrees) => collection.with_rotation(*degrees),
+                            SceneTransform::RotationAround(degrees, point) => {
+                                collection.with_rotation_around(*degrees, *point)
+                            }
+                            SceneTransform::Scale(scale) => collection.with_scale(*scale),
+                            SceneTransform::ScaleAround(scale, point) => {
+                                collection.with_scale_around(*scale, *point)
+                            }
+                            SceneTransform::Move(xy) => collection.with_move(*xy),
+                            SceneTransform::Translation(delta) => {
+                                collection.with_translation(*delta)
+                            }
+                        };
+                    }
+                    graphics.draw(&collection);
+                }
+                SceneItem::Text {
+                    text,
+                    pos,
+                    color: rgba,
+                    font,
+                    positioning,
+                    wrapping,
+                } => graphics.draw_text(
+                    text,
+                    TextPos::px(*pos),
+                    (
+                        color(*rgba),
+                        font.unwrap_or(PixelFont::Standard6x7),
+                        wrapping.unwrap_or(WrappingStrategy::None),
+                        positioning.unwrap_or(Positioning::LeftTop),
+                    ),
+                ),
+                SceneItem::Image { pos, image, .. } => {
+                    if let Some(image) = image {
+                        graphics.draw_indexed_image(*pos, image);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Load every scene in `dir`, in file name order
+///
+/// Files that fail to load are skipped and reported in the returned errors
+pub fn load_all(dir: &Path) -> (Vec<Scene>, Vec<String>) {
+    let mut scenes = vec![];
+    let mut errors = vec![];
+    for path in scene_files(dir) {
+        match Scene::load(&path) {
+            Ok(scene) => scenes.push(scene),
+            Err(e) => errors.push(format!("{e:#}")),
+        }
+    }
+    (scenes, errors)
+}
+
+/// Every `.ron` file in `dir`, sorted by name
+pub fn scene_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map(|ext| ext == "ron").unwrap_or(false))
+        .collect();
+    paths.sort();
+    paths
+}
@@ -0,0 +1,278 @@
+//! Snapshot checks
+//!
+//! Run with `graphics_tester check [--baselines DIR] [--out DIR] [--update] [--junit FILE]`, every
+//! test is rendered offscreen and compared pixel by pixel against `DIR/<name>.png`. The renders
+//! and diff images are written to the output directory along with `report.html`. `--update`
+//! replaces the baselines with the current renders instead. The stress pages are skipped as they
+//! show timings.
+//!
+//! `--include` and `--exclude` take comma separated category and tag names (see [crate::category]) to
+//! only check some of the tests, i.e. `--include text` after a change to text layout.
+//!
+//! The assets are checked first, as the tests can't be rendered without them. With `--junit` the
+//! invariant suites are run as well, and everything is written to `FILE` as JUnit XML.
+
+use crate::category::Filter;
+use crate::cli::Common;
+use crate::{assets, capture, junit, report, Example, Stress};
+use anyhow::{bail, Context, Result};
+use pixels_graphics_lib::prelude::*;
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_BASELINES: &str = "baselines";
+pub const DEFAULT_OUTPUT: &str = "snapshots";
+pub const REPORT_FILE: &str = "report.html";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Pass,
+    /// Number of pixels that differ from the baseline
+    Mismatch(usize),
+    /// Baseline width and height
+    SizeMismatch(usize, usize),
+    NoBaseline,
+    /// Baseline couldn't be read, or the render couldn't be saved
+    Error(String),
+    Panicked(String),
+}
+
+impl Status {
+    pub fn passed(&self) -> bool {
+        *self == Status::Pass
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Status::Pass => String::from("matches baseline"),
+            Status::Mismatch(count) => format!("{count} pixels differ from baseline"),
+            Status::SizeMismatch(width, height) => format!("baseline is {width}x{height}"),
+            Status::NoBaseline => String::from("no baseline"),
+            Status::Error(message) => message.clone(),
+            Status::Panicked(message) => message.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    /// Text passed to `draw_title`
+    pub title: String,
+    pub status: Status,
+    pub render_time: Duration,
+    pub rendered: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub diff: Option<PathBuf>,
+}
+
+pub fn run(
+    baselines: &Path,
+    output: &Path,
+    update: bool,
+    junit: Option<&Path>,
+    filter: &Filter,
+    common: &Common,
+) -> Result<()> {
+    //the tests can't be rendered without their assets, so check those before anything else
+    println!("Assets:");
+    let assets = junit::asset_suite(assets::check_all());
+    let broken = assets.errors();
+    if broken > 0 {
+        if let Some(path) = junit {
+            junit::write(path, &[assets])?;
+            println!("JUnit XML written to {}", path.display());
+        }
+        bail!("{broken} asset(s) failed to load");
+    }
+
+    let results = render_all(baselines, output, update, filter, common.size())?;
+    if results.is_empty() {
+        bail!("no tests match the filters");
+    }
+    if update {
+        println!(
+            "Updated {} baselines in {}",
+            results.len(),
+            baselines.display()
+        );
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for result in &results {
+        if result.status.passed() {
+            println!("[PASS] {}", result.title);
+        } else {
+            failed += 1;
+            println!("[FAIL] {}: {}", result.title, result.status.describe());
+        }
+    }
+    let report_path = output.join(REPORT_FILE);
+    report::write_html(&report_path, &results)?;
+    println!("Report written to {}", report_path.display());
+    let mut total = results.len();
+
+    if let Some(path) = junit {
+        println!("Invariants:");
+        let invariants = junit::invariant_suite(common.seed);
+        let suites = [junit::snapshot_suite(&results), invariants, assets];
+        for suite in &suites[1..] {
+            total += suite.cases.len();
+            failed += suite.failures() + suite.errors();
+        }
+        junit::write(path, &suites)?;
+        println!("JUnit XML written to {}", path.display());
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {total} test(s) failed");
+    }
+    Ok(())
+}
+
+/// Render every test, saving the renders to `output` and comparing them to `baselines` (or
+/// replacing the baselines if `update` is set)
+pub fn render_all(
+    baselines: &Path,
+    output: &Path,
+    update: bool,
+    filter: &Filter,
+    size: (usize, usize),
+) -> Result<Vec<TestResult>> {
+    fs::create_dir_all(output).with_context(|| format!("creating {}", output.display()))?;
+    if update {
+        fs::create_dir_all(baselines)
+            .with_context(|| format!("creating {}", baselines.display()))?;
+    }
+
+    let mut example = Example::new();
+    let tests: Vec<usize> = (0..example.test_count())
+        .filter(|test| !Stress::is_stress(*test) && filter.matches(*test))
+        .collect();
+
+    let mut results = vec![];
+    example.render_tests(tests, size, None, |rendered| {
+        let stem = capture::file_stem(rendered.test, &rendered.title);
+        let mut result = TestResult {
+            title: rendered.title,
+            status: Status::Pass,
+            render_time: rendered.time,
+            rendered: None,
+            baseline: None,
+            diff: None,
+        };
+        let image = match rendered.image {
+            Ok(image) => image,
+            Err(message) => {
+                result.status = Status::Panicked(message);
+                results.push(result);
+                return Ok(());
+            }
+        };
+
+        let baseline_path = baselines.join(format!("{stem}.png"));
+        if update {
+            capture::save_png(&baseline_path, &image)?;
+            result.baseline = Some(baseline_path);
+            results.push(result);
+            return Ok(());
+        }
+        let rendered_path = output.join(format!("{stem}.png"));
+        if let Err(e) = capture::save_png(&rendered_path, &image) {
+            result.status = Status::Error(format!("{e:#}"));
+            results.push(result);
+            return Ok(());
+        }
+        result.rendered = Some(rendered_path);
+        if !baseline_path.exists() {
+            result.status = Status::NoBaseline;
+            results.push(result);
+            return Ok(());
+        }
+        result.baseline = Some(baseline_path.clone());
+        result.status = match load_png(&baseline_path) {
+            Ok(baseline) => compare(&image, &baseline, output, &stem, &mut result.diff),
+            Err(e) => Status::Error(format!("{e:#}")),
+        };
+        results.push(result);
+        Ok(())
+    })?;
+    Ok(results)
+}
+
+/// Compare `image` to `baseline`, writing a diff image to `output` if they differ
+fn compare(
+    image: &Image,
+    baseline: &Image,
+    output: &Path,
+    stem: &str,
+    diff_path: &mut Option<PathBuf>,
+) -> Status {
+    if image.width() != baseline.width() || image.height() != baseline.height() {
+        return Status::SizeMismatch(baseline.width(), baseline.height());
+    }
+    let (diff, mismatched) = diff_image(image, baseline);
+    if mismatched == 0 {
+        return Status::Pass;
+    }
+    let path = output.join(format!("{stem}_diff.png"));
+    match capture::save_png(&path, &diff) {
+        Ok(()) => {
+            *diff_path = Some(path);
+            Status::Mismatch(mismatched)
+        }
+        Err(e) => Status::Error(format!("{mismatched} pixels differ, {e:#}")),
+    }
+}
+
+/// Differing pixels in red over a dimmed greyscale copy of `image`, and the number that differ
+pub fn diff_image(image: &Image, baseline: &Image) -> (Image, usize) {
+    let mut mismatched = 0;
+    let pixels = image
+        .pixels()
+        .iter()
+        .zip(baseline.pixels())
+        .map(|(actual, expected)| {
+            if actual == expected {
+                let grey = ((actual.r as u16 + actual.g as u16 + actual.b as u16) / 9) as u8;
+                Color::gray(grey)
+            } else {
+                mismatched += 1;
+                RED
+            }
+        })
+        .collect();
+    let diff =
+        Image::new(pixels, image.width(), image.height()).expect("diff has the same size as image");
+    (diff, mismatched)
+}
+
+pub fn load_png(path: &Path) -> Result<Image> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder
+        .read_info()
+        .with_context(|| format!("reading {}", path.display()))?;
+    let mut bytes = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut bytes)
+        .with_context(|| format!("decoding {}", path.display()))?;
+    let channels = match info.color_type {
+        png::ColorType::Rgba => 4,
+        png::ColorType::Rgb => 3,
+        other => bail!("{} is {other:?}, expected RGB or RGBA", path.display()),
+    };
+    if info.bit_depth != png::BitDepth::Eight {
+        bail!("{} is {:?} bit, expected 8", path.display(), info.bit_depth);
+    }
+    let pixels = bytes[..info.buffer_size()]
+        .chunks_exact(channels)
+        .map(|px| Color::new(px[0], px[1], px[2], px.get(3).copied().unwrap_or(255)))
+        .collect();
+    Image::new(pixels, info.width as usize, info.height as usize)
+        .map_err(|e| anyhow::anyhow!("loading {}: {e:?}", path.display()))
+}